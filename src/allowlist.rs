@@ -0,0 +1,187 @@
+use crate::host::{parse_host, Host, HostError};
+use crate::url::URL;
+use crate::uts46::Uts46Options;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::net::IpAddr;
+
+/// One entry of a host allowlist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HostPattern {
+    /// A domain or IP address, like "api.example.net" or "[::1]", that only matches itself.
+    Exact(Host),
+    /// "*.example.com", which matches the subdomains of example.com but not example.com itself.
+    Subdomains(String),
+    /// ".example.org", which matches example.org and its subdomains.
+    DomainAndSubdomains(String),
+    /// An IPv4 or IPv6 range in CIDR notation, like "10.0.0.0/8" or "fe80::/10".
+    Cidr(IpAddr, u8),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternErrorKind {
+    /// The host of the pattern could not be parsed.
+    Host(HostError),
+    /// A "*" that is not the whole first label, or a wildcard or suffix pattern for an IP address.
+    Wildcard,
+    /// A CIDR range whose address is not an IP address, or whose prefix length is too long.
+    Cidr,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternError {
+    /// The index of the offending pattern in the list.
+    pub index: usize,
+    pub kind: PatternErrorKind,
+}
+
+impl HostPattern {
+    /// Patterns go through host parsing, so "*.BÜCHER.example" matches "www.xn--bcher-kva.example".
+    pub fn parse(pattern: &str) -> Result<HostPattern, PatternErrorKind> {
+        if let Some((address, prefix_length)) = pattern.split_once('/') {
+            let address = address
+                .strip_prefix('[')
+                .and_then(|address| address.strip_suffix(']'))
+                .unwrap_or(address);
+            let address: IpAddr = address.parse().map_err(|_| PatternErrorKind::Cidr)?;
+            let max_length = if address.is_ipv4() { 32 } else { 128 };
+
+            return match prefix_length.parse::<u8>() {
+                Ok(length)
+                    if length <= max_length
+                        && prefix_length.bytes().all(|c| c.is_ascii_digit()) =>
+                {
+                    Ok(HostPattern::Cidr(address, length))
+                }
+                _ => Err(PatternErrorKind::Cidr),
+            };
+        }
+
+        let (pattern, make): (&str, fn(String) -> HostPattern) =
+            if let Some(domain) = pattern.strip_prefix("*.") {
+                (domain, HostPattern::Subdomains)
+            } else if let Some(domain) = pattern.strip_prefix('.') {
+                (domain, HostPattern::DomainAndSubdomains)
+            } else {
+                let host = parse_domain_or_ip(pattern)?;
+                return Ok(HostPattern::Exact(host));
+            };
+
+        match parse_domain_or_ip(pattern)? {
+            Host::Domain(domain) => Ok(make(domain)),
+            _ => Err(PatternErrorKind::Wildcard),
+        }
+    }
+
+    /// IPv4-mapped IPv6 addresses match as the IPv4 address they map. Other IPv6 addresses that embed an
+    /// IPv4 address, like 6to4 and NAT64 ones, are different hosts and only match IPv6 patterns.
+    pub fn matches(&self, host: &Host) -> bool {
+        match (self, host) {
+            (HostPattern::Exact(Host::Domain(pattern)), Host::Domain(domain)) => {
+                without_trailing_dot(domain) == pattern
+            }
+            (HostPattern::Exact(pattern), host) => match (pattern.ip(), host.ip()) {
+                (Some(pattern), Some(address)) => unmapped(pattern) == unmapped(address),
+                _ => false,
+            },
+            (HostPattern::Subdomains(pattern), Host::Domain(domain)) => {
+                is_subdomain(without_trailing_dot(domain), pattern)
+            }
+            (HostPattern::DomainAndSubdomains(pattern), Host::Domain(domain)) => {
+                let domain = without_trailing_dot(domain);
+                domain == pattern || is_subdomain(domain, pattern)
+            }
+            (HostPattern::Cidr(network, length), host) => host.ip().is_some_and(|address| {
+                let address = if network.is_ipv4() {
+                    unmapped(address)
+                } else {
+                    address
+                };
+                in_range(address, *network, *length)
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// The IPv4 address of an IPv4-mapped address (::ffff:0:0/96), and the address itself otherwise.
+fn unmapped(address: IpAddr) -> IpAddr {
+    match address {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(address, IpAddr::V4),
+        IpAddr::V4(_) => address,
+    }
+}
+
+/// A compiled list of `HostPattern`s. A host is allowed if any of them matches it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostAllowlist {
+    patterns: Vec<HostPattern>,
+}
+
+impl HostAllowlist {
+    pub fn parse<'a, I: IntoIterator<Item = &'a str>>(
+        patterns: I,
+    ) -> Result<HostAllowlist, PatternError> {
+        let patterns = patterns
+            .into_iter()
+            .enumerate()
+            .map(|(index, pattern)| {
+                HostPattern::parse(pattern.trim()).map_err(|kind| PatternError { index, kind })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(HostAllowlist { patterns })
+    }
+
+    pub fn patterns(&self) -> &[HostPattern] {
+        &self.patterns
+    }
+
+    pub fn matches(&self, host: &Host) -> bool {
+        self.patterns.iter().any(|pattern| pattern.matches(host))
+    }
+
+    /// Returns true if the URL has a host and it matches the list. Opaque hosts never match.
+    pub fn allows(&self, url: &URL) -> bool {
+        url.parsed_host().is_some_and(|host| self.matches(&host))
+    }
+}
+
+fn parse_domain_or_ip(pattern: &str) -> Result<Host, PatternErrorKind> {
+    if pattern.contains('*') {
+        return Err(PatternErrorKind::Wildcard);
+    }
+
+    let host =
+        parse_host(pattern, false, &Uts46Options::default()).map_err(PatternErrorKind::Host)?;
+
+    Ok(match host {
+        Host::Domain(domain) => Host::Domain(String::from(without_trailing_dot(&domain))),
+        host => host,
+    })
+}
+
+fn without_trailing_dot(domain: &str) -> &str {
+    domain.strip_suffix('.').unwrap_or(domain)
+}
+
+/// Returns true if the domain ends with a U+002E (.) followed by the parent.
+fn is_subdomain(domain: &str, parent: &str) -> bool {
+    domain
+        .strip_suffix(parent)
+        .is_some_and(|label| label.len() > 1 && label.ends_with('.'))
+}
+
+fn in_range(address: IpAddr, network: IpAddr, length: u8) -> bool {
+    match (address, network) {
+        (IpAddr::V4(address), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - length as u32).unwrap_or(0);
+            u32::from(address) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(address), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - length as u32).unwrap_or(0);
+            u128::from(address) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
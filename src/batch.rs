@@ -0,0 +1,100 @@
+use crate::machine::{ParseOptions, Scratch, URLStateMachine};
+use crate::url::URL;
+use alloc::vec::Vec;
+
+/// Parses many inputs against the same base URL.
+///
+/// The base is parsed once and shared by every run of the `URLStateMachine`,
+/// and the machine's buffers are reused from one input to the next.
+#[derive(Default)]
+pub struct Parser {
+    base: Option<URL>,
+    scratch: Scratch,
+}
+
+impl Parser {
+    pub fn new(base: Option<URL>) -> Parser {
+        Parser {
+            base,
+            scratch: Scratch::default(),
+        }
+    }
+
+    /// Returns `None` if the base is not a valid URL.
+    pub fn with_base(base: &str) -> Option<Parser> {
+        let (url, scratch) = URLStateMachine::new(base, None, None, None).finish();
+
+        Some(Parser {
+            base: Some(url?),
+            scratch,
+        })
+    }
+
+    pub fn base(&self) -> Option<&URL> {
+        self.base.as_ref()
+    }
+
+    /// Returns `None` if the input is not a valid URL.
+    pub fn parse(&mut self, input: &str) -> Option<URL> {
+        let scratch = core::mem::take(&mut self.scratch);
+        let (url, scratch) = parse_with_scratch(self.base.as_ref(), input, scratch);
+        self.scratch = scratch;
+
+        url
+    }
+
+    /// Returns true if the input parses against the base, without building its components.
+    pub fn can_parse(&mut self, input: &str) -> bool {
+        let scratch = core::mem::take(&mut self.scratch);
+        let machine = URLStateMachine::with_scratch(
+            input,
+            self.base.as_ref(),
+            None,
+            None,
+            scratch,
+            true,
+            ParseOptions::default(),
+        );
+        let failure = machine.failure;
+        self.scratch = machine.finish().1;
+
+        !failure
+    }
+
+    pub fn parse_many<'i, I>(&mut self, inputs: I) -> Vec<Option<URL>>
+    where
+        I: IntoIterator<Item = &'i str>,
+    {
+        inputs.into_iter().map(|input| self.parse(input)).collect()
+    }
+
+    /// Parses the inputs on the rayon thread pool, with one set of buffers per worker.
+    #[cfg(feature = "parallel")]
+    pub fn par_parse_many(&self, inputs: &[&str]) -> Vec<Option<URL>> {
+        use rayon::prelude::*;
+
+        inputs
+            .par_iter()
+            .map_init(Scratch::default, |scratch, input| {
+                let (url, next) =
+                    parse_with_scratch(self.base.as_ref(), input, core::mem::take(scratch));
+                *scratch = next;
+
+                url
+            })
+            .collect()
+    }
+}
+
+fn parse_with_scratch(base: Option<&URL>, input: &str, scratch: Scratch) -> (Option<URL>, Scratch) {
+    URLStateMachine::with_scratch(
+        input,
+        base,
+        None,
+        None,
+        scratch,
+        false,
+        ParseOptions::default(),
+    )
+    .finish()
+}
@@ -0,0 +1,176 @@
+use crate::host::{parse_host, Host};
+use crate::psl::PublicSuffixList;
+use crate::url::URL;
+use crate::uts46::Uts46Options;
+use alloc::string::{String, ToString};
+use core::net::{IpAddr, Ipv4Addr};
+
+/// The domain a cookie is stored for, from https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#section-5.7
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CookieDomain {
+    /// The host-only-flag is set: the cookie is only sent to this exact host.
+    HostOnly(String),
+    /// The cookie is sent to this domain and every domain that domain-matches it.
+    Domain(String),
+}
+
+impl CookieDomain {
+    /// Returns true if the cookie should be sent to the canonicalized host of a request.
+    pub fn matches(&self, host: &str) -> bool {
+        match self {
+            CookieDomain::HostOnly(domain) => host == domain,
+            CookieDomain::Domain(domain) => domain_match(host, domain),
+        }
+    }
+}
+
+/// The reasons a cookie is ignored because of its Domain attribute.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CookieDomainError {
+    /// The request URL has no host, or an opaque one.
+    NoHost,
+    /// The Domain attribute is not a valid domain.
+    InvalidDomain,
+    /// The request host is an IP address and the Domain attribute is not that same address.
+    IpAddress,
+    /// The Domain attribute is a public suffix other than the request host.
+    PublicSuffix,
+    /// The request host does not domain-match the Domain attribute.
+    NotDomainMatch,
+}
+
+/// https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-domain-matching
+///
+/// Both strings are expected to be canonicalized, as the hosts of the URL parser are.
+pub fn domain_match(string: &str, domain: &str) -> bool {
+    // The domain string and the string are identical.
+    if string == domain {
+        return true;
+    }
+
+    // The domain string is a suffix of the string, the last character of the string that is not
+    // included in the domain string is a %x2E ("."), and the string is a host name (i.e., not an IP address).
+    string
+        .strip_suffix(domain)
+        .is_some_and(|rest| rest.ends_with('.'))
+        && !is_ip_address(string)
+}
+
+/// https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-paths-and-path-match
+pub fn path_match(request_path: &str, cookie_path: &str) -> bool {
+    // The cookie-path and the request-path are identical.
+    if request_path == cookie_path {
+        return true;
+    }
+
+    match request_path.strip_prefix(cookie_path) {
+        // The cookie-path is a prefix of the request-path, and the last character of the cookie-path is %x2F ("/").
+        // The cookie-path is a prefix of the request-path, and the first character of the request-path that is not
+        // included in the cookie-path is a %x2F ("/") character.
+        Some(rest) => cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-paths-and-path-match
+pub fn default_path(url: &URL) -> String {
+    // Let uri-path be the path portion of the request-uri if such a portion exists (and empty otherwise).
+    let uri_path = url.pathname();
+
+    // If the uri-path is empty or if the first character of the uri-path is not a %x2F ("/") character,
+    // output %x2F ("/") and skip the remaining steps.
+    // If the uri-path contains no more than one %x2F ("/") character, output %x2F ("/") and skip the remaining step.
+    match uri_path.rfind('/') {
+        // Output the characters of the uri-path from the first character up to, but not including, the right-most %x2F ("/").
+        Some(index) if index > 0 && uri_path.starts_with('/') => uri_path[..index].to_string(),
+        _ => "/".to_string(),
+    }
+}
+
+/// The cookie-path of a cookie with the given Path attribute, set by a response to the URL.
+///
+/// https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-the-path-attribute
+pub fn cookie_path(url: &URL, path_attribute: Option<&str>) -> String {
+    match path_attribute {
+        // If the attribute-value is empty or if the first character of the attribute-value is not %x2F ("/"),
+        // let cookie-path be the default-path.
+        Some(path) if path.starts_with('/') => path.to_string(),
+        _ => default_path(url),
+    }
+}
+
+/// The domain of a cookie with the given Domain attribute, set by a response to the URL. Public
+/// suffixes are looked up in the given list, with the rules of both of its sections.
+///
+/// https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-storage-model, steps 5 to 7.
+pub fn cookie_domain(
+    url: &URL,
+    domain_attribute: Option<&str>,
+    list: &PublicSuffixList,
+) -> Result<CookieDomain, CookieDomainError> {
+    let host = url.parsed_host().ok_or(CookieDomainError::NoHost)?;
+
+    if matches!(host, Host::Opaque(_)) {
+        return Err(CookieDomainError::NoHost);
+    }
+
+    // The canonicalized request-host.
+    let request_host = host.to_string();
+
+    // If the cookie-attribute-list contains an attribute with an attribute-name of "Domain",
+    // let the domain-attribute be the attribute-value of the last such attribute. Otherwise, the empty string.
+    // A leading %x2E (".") character is removed.
+    let domain_attribute = domain_attribute
+        .map(|domain| domain.strip_prefix('.').unwrap_or(domain))
+        .unwrap_or("");
+
+    // If the domain-attribute is empty, set the cookie's host-only-flag to true and the cookie's domain to the canonicalized request-host.
+    if domain_attribute.is_empty() {
+        return Ok(CookieDomain::HostOnly(request_host));
+    }
+
+    // IP addresses are never domain cookies. An attribute that names the address itself is treated as absent.
+    if host.ip().is_some() {
+        let attribute = domain_attribute
+            .strip_prefix('[')
+            .and_then(|domain| domain.strip_suffix(']'))
+            .unwrap_or(domain_attribute);
+
+        return match (host.ip(), attribute.parse::<IpAddr>().ok()) {
+            (Some(address), Some(attribute)) if address == attribute => {
+                Ok(CookieDomain::HostOnly(request_host))
+            }
+            _ => Err(CookieDomainError::IpAddress),
+        };
+    }
+
+    // The domain-attribute goes through host parsing, to be compared with the canonicalized request-host.
+    let cookie_domain = match parse_host(domain_attribute, false, &Uts46Options::default()) {
+        Ok(Host::Domain(domain)) => domain,
+        Ok(_) => return Err(CookieDomainError::NotDomainMatch),
+        Err(_) => return Err(CookieDomainError::InvalidDomain),
+    };
+
+    // If the user agent is configured to reject "public suffixes" and the domain-attribute is a public suffix:
+    if list.public_suffix(&cookie_domain) == Some(cookie_domain.as_str()) {
+        // If the domain-attribute is identical to the canonicalized request-host, let the domain-attribute be the empty string.
+        if cookie_domain == request_host {
+            return Ok(CookieDomain::HostOnly(request_host));
+        }
+
+        // Otherwise, ignore the cookie entirely and abort these steps.
+        return Err(CookieDomainError::PublicSuffix);
+    }
+
+    // If the canonicalized request-host does not domain-match the domain-attribute, ignore the cookie entirely and abort these steps.
+    if !domain_match(&request_host, &cookie_domain) {
+        return Err(CookieDomainError::NotDomainMatch);
+    }
+
+    // Set the cookie's host-only-flag to false and the cookie's domain to the domain-attribute.
+    Ok(CookieDomain::Domain(cookie_domain))
+}
+
+fn is_ip_address(host: &str) -> bool {
+    host.starts_with('[') || host.parse::<Ipv4Addr>().is_ok()
+}
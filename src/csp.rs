@@ -0,0 +1,378 @@
+use crate::host::Host;
+use crate::origin::Origin;
+use crate::scheme::SchemeType;
+use crate::url::URL;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use percent_encoding::percent_decode_str;
+
+/// https://w3c.github.io/webappsec-csp/#source-expression
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SourceExpression {
+    /// "*"
+    Any,
+    /// https://w3c.github.io/webappsec-csp/#grammardef-scheme-source, without the ":" and lowercased.
+    Scheme(String),
+    /// https://w3c.github.io/webappsec-csp/#grammardef-host-source
+    Host(HostSource),
+    /// "'self'"
+    SelfOrigin,
+    /// "'none'"
+    None,
+    /// Keywords, nonces, hashes and invalid expressions, which never match a URL.
+    Other(String),
+}
+
+/// https://w3c.github.io/webappsec-csp/#grammardef-host-source
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostSource {
+    /// Lowercased.
+    pub scheme: Option<String>,
+    /// "*", or a domain optionally starting with "*.".
+    pub host: String,
+    pub port: Option<PortPart>,
+    pub path: Option<String>,
+}
+
+/// https://w3c.github.io/webappsec-csp/#grammardef-port-part
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PortPart {
+    Any,
+    Port(u16),
+}
+
+/// https://w3c.github.io/webappsec-csp/#grammardef-serialized-source-list
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceList(Vec<SourceExpression>);
+
+impl SourceList {
+    /// Splits the directive value on ASCII whitespace. Expressions that are not valid are kept as
+    /// `SourceExpression::Other`, so that they count towards the size of the list.
+    pub fn parse(input: &str) -> SourceList {
+        SourceList(
+            input
+                .split_ascii_whitespace()
+                .map(SourceExpression::parse)
+                .collect(),
+        )
+    }
+
+    pub fn expressions(&self) -> &[SourceExpression] {
+        &self.0
+    }
+
+    /// https://w3c.github.io/webappsec-csp/#match-url-to-source-list
+    pub fn matches(&self, url: &URL, origin: &Origin, redirect_count: usize) -> bool {
+        // If source list is empty, return "Does Not Match".
+        // If source list’s size is 1, and source list[0] is an ASCII case-insensitive match for the string "'none'",
+        // then return "Does Not Match".
+        if let [] | [SourceExpression::None] = self.0.as_slice() {
+            return false;
+        }
+
+        // For each expression of source list:
+        // If § 6.7.2.6 Does url match expression in origin with redirect count? returns "Matches", return "Matches".
+        // Return "Does Not Match".
+        self.0
+            .iter()
+            .any(|expression| expression.matches(url, origin, redirect_count))
+    }
+}
+
+impl SourceExpression {
+    pub fn parse(input: &str) -> SourceExpression {
+        if input == "*" {
+            return SourceExpression::Any;
+        }
+
+        if input.eq_ignore_ascii_case("'self'") {
+            return SourceExpression::SelfOrigin;
+        }
+
+        if input.eq_ignore_ascii_case("'none'") {
+            return SourceExpression::None;
+        }
+
+        if let Some(scheme) = input.strip_suffix(':') {
+            if is_scheme(scheme) {
+                return SourceExpression::Scheme(scheme.to_ascii_lowercase());
+            }
+        }
+
+        match parse_host_source(input) {
+            Some(source) => SourceExpression::Host(source),
+            None => SourceExpression::Other(input.to_string()),
+        }
+    }
+
+    /// https://w3c.github.io/webappsec-csp/#match-url-to-source-expression
+    pub fn matches(&self, url: &URL, origin: &Origin, redirect_count: usize) -> bool {
+        let origin_scheme = match origin {
+            Origin::Tuple(origin) => Some(origin.scheme.as_str()),
+            Origin::Opaque(_) => None,
+        };
+
+        match self {
+            // If expression is the string "*", return "Matches" if one or more of the following conditions is met:
+            // url’s scheme is an HTTP(S) scheme.
+            // url’s scheme is the same as origin’s scheme.
+            SourceExpression::Any => {
+                matches!(url.scheme_type, SchemeType::Http | SchemeType::Https)
+                    || origin_scheme == Some(url.scheme.as_str())
+            }
+            // If expression matches the scheme-source or host-source grammar:
+            // If expression has a scheme-part, and it does not scheme-part match url’s scheme, return "Does Not Match".
+            // If expression matches the scheme-source grammar, return "Matches".
+            SourceExpression::Scheme(scheme) => scheme_part_match(scheme, &url.scheme),
+            SourceExpression::Host(source) => {
+                if let Some(scheme) = &source.scheme {
+                    if !scheme_part_match(scheme, &url.scheme) {
+                        return false;
+                    }
+                }
+
+                // If url’s host is null, return "Does Not Match".
+                let Some(host) = url.parsed_host() else {
+                    return false;
+                };
+
+                // If expression does not have a scheme-part, and origin’s scheme does not scheme-part match url’s scheme,
+                // return "Does Not Match".
+                if source.scheme.is_none()
+                    && !origin_scheme.is_some_and(|scheme| scheme_part_match(scheme, &url.scheme))
+                {
+                    return false;
+                }
+
+                // If expression’s host-part does not host-part match url’s host, return "Does Not Match".
+                if !host_part_match(&source.host, &host) {
+                    return false;
+                }
+
+                // Let port-part be expression’s port-part if present, and null otherwise.
+                // If port-part does not port-part match url, return "Does Not Match".
+                if !port_part_match(source.port, url) {
+                    return false;
+                }
+
+                // If expression contains a non-empty path-part, and redirect count is 0, then:
+                if let Some(path) = &source.path {
+                    // Let path be the result of running the URL path serializer on url.
+                    // If expression’s path-part does not path-part match path, return "Does Not Match".
+                    if redirect_count == 0 && !path_part_match(path, &url.pathname()) {
+                        return false;
+                    }
+                }
+
+                // Return "Matches".
+                true
+            }
+            // If expression is an ASCII case-insensitive match for "'self'", return "Matches" if one or more of the
+            // following conditions is met:
+            SourceExpression::SelfOrigin => {
+                // origin is the same as url’s origin
+                if origin.same_origin(&url.origin()) {
+                    return true;
+                }
+
+                // origin’s host is the same as url’s host, origin’s port and url’s port are either the same or the
+                // default ports for their respective schemes, and one or more of the following conditions is met:
+                // url’s scheme is "https" or "wss"
+                // origin’s scheme is "http" and url’s scheme is "http" or "ws"
+                let Origin::Tuple(origin) = origin else {
+                    return false;
+                };
+
+                let origin_default_port = SchemeType::from(origin.scheme.as_str()).default_port();
+                let origin_port = origin.port.or(origin_default_port);
+                let url_port = match &url.port {
+                    Some(port) => port.parse::<u16>().ok(),
+                    None => url.scheme_type.default_port(),
+                };
+                let is_same_port = origin_port == url_port
+                    || (origin_port == origin_default_port
+                        && url_port == url.scheme_type.default_port());
+
+                url.parsed_host().as_ref() == Some(&origin.host)
+                    && is_same_port
+                    && (matches!(url.scheme_type, SchemeType::Https | SchemeType::Wss)
+                        || (origin.scheme == "http"
+                            && matches!(url.scheme_type, SchemeType::Http | SchemeType::Ws)))
+            }
+            // Return "Does Not Match".
+            SourceExpression::None | SourceExpression::Other(_) => false,
+        }
+    }
+}
+
+/// scheme-part = scheme
+fn is_scheme(input: &str) -> bool {
+    let mut chars = input.chars();
+
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// host-source = [ scheme-part "://" ] host-part [ ":" port-part ] [ path-part ]
+fn parse_host_source(input: &str) -> Option<HostSource> {
+    let (scheme, rest) = match input.split_once("://") {
+        Some((scheme, rest)) if is_scheme(scheme) => (Some(scheme.to_ascii_lowercase()), rest),
+        Some(_) => return None,
+        None => (None, input),
+    };
+
+    // path-part = path-absolute (but not including ";" or ",")
+    let (rest, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], Some(&rest[index..])),
+        None => (rest, None),
+    };
+
+    if path.is_some_and(|path| path.starts_with("//") || path.contains([';', ','])) {
+        return None;
+    }
+
+    // port-part = 1*DIGIT / "*"
+    let (host, port) = match rest.split_once(':') {
+        Some((host, "*")) => (host, Some(PortPart::Any)),
+        Some((host, port)) if !port.is_empty() && port.bytes().all(|c| c.is_ascii_digit()) => {
+            (host, Some(PortPart::Port(port.parse().ok()?)))
+        }
+        Some(_) => return None,
+        None => (rest, None),
+    };
+
+    // host-part = "*" / [ "*." ] 1*host-char *( "." 1*host-char ) [ "." ]
+    // host-char = ALPHA / DIGIT / "-"
+    let labels = host.strip_prefix("*.").unwrap_or(host);
+    let labels = labels.strip_suffix('.').unwrap_or(labels);
+
+    if host != "*"
+        && !labels.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .bytes()
+                    .all(|c| c.is_ascii_alphanumeric() || c == b'-')
+        })
+    {
+        return None;
+    }
+
+    Some(HostSource {
+        scheme,
+        host: host.to_string(),
+        port,
+        path: path.map(String::from),
+    })
+}
+
+/// https://w3c.github.io/webappsec-csp/#scheme-part-match
+fn scheme_part_match(a: &str, b: &str) -> bool {
+    // If one of the following is true, return "Matches":
+    // A is an ASCII case-insensitive match for B.
+    // A is an ASCII case-insensitive match for "http", and B is "https".
+    // A is an ASCII case-insensitive match for "ws", and B is "wss", "http", or "https".
+    // A is an ASCII case-insensitive match for "wss", and B is "https".
+    // Return "Does Not Match".
+    a.eq_ignore_ascii_case(b)
+        || (a.eq_ignore_ascii_case("http") && b == "https")
+        || (a.eq_ignore_ascii_case("ws") && matches!(b, "wss" | "http" | "https"))
+        || (a.eq_ignore_ascii_case("wss") && b == "https")
+}
+
+/// https://w3c.github.io/webappsec-csp/#host-part-match
+fn host_part_match(pattern: &str, host: &Host) -> bool {
+    // If host is not a domain, return "Does Not Match".
+    let Host::Domain(host) = host else {
+        return false;
+    };
+
+    // If pattern is "*", return "Matches".
+    if pattern == "*" {
+        return true;
+    }
+
+    // If pattern starts with "*.":
+    if let Some(remaining) = pattern.strip_prefix('*') {
+        if remaining.starts_with('.') {
+            // Let remaining be pattern with the leading U+002A (*) removed and ASCII lowercased.
+            // If host to ASCII lowercase ends with remaining, then return "Matches".
+            // Return "Does Not Match".
+            return host
+                .to_ascii_lowercase()
+                .ends_with(&remaining.to_ascii_lowercase());
+        }
+    }
+
+    // If pattern is not an ASCII case-insensitive match for host, return "Does Not Match".
+    // Return "Matches".
+    pattern.eq_ignore_ascii_case(host)
+}
+
+/// https://w3c.github.io/webappsec-csp/#port-part-matches
+fn port_part_match(input: Option<PortPart>, url: &URL) -> bool {
+    let url_port = url.port.as_ref().and_then(|port| port.parse::<u16>().ok());
+
+    match input {
+        // If input is "*", return "Matches".
+        Some(PortPart::Any) => true,
+        // Let normalizedInput be null if input is null; otherwise input interpreted as decimal number.
+        // If normalizedInput equals url’s port, return "Matches".
+        // If url’s port is null:
+        // Let defaultPort be the default port for url’s scheme.
+        // If normalizedInput equals defaultPort, return "Matches".
+        // Return "Does Not Match".
+        Some(PortPart::Port(port)) => {
+            url_port == Some(port)
+                || (url.port.is_none() && url.scheme_type.default_port() == Some(port))
+        }
+        None => url.port.is_none(),
+    }
+}
+
+/// https://w3c.github.io/webappsec-csp/#path-part-match
+fn path_part_match(path_a: &str, path_b: &str) -> bool {
+    // If path A is the empty string, return "Matches".
+    // If path A consists of one character that is equal to the U+002F SOLIDUS character (/) and path B is the
+    // empty string, return "Matches".
+    if path_a.is_empty() || (path_a == "/" && path_b.is_empty()) {
+        return true;
+    }
+
+    // Let exact match be false if the final character of path A is the U+002F SOLIDUS character (/), and true
+    // otherwise.
+    let exact_match = !path_a.ends_with('/');
+
+    // Let path list A and path list B be the result of strictly splitting path A and path B respectively on the
+    // U+002F SOLIDUS character (/).
+    let mut path_list_a: Vec<&str> = path_a.split('/').collect();
+    let path_list_b: Vec<&str> = path_b.split('/').collect();
+
+    // If path list A has more items than path list B, return "Does Not Match".
+    if path_list_a.len() > path_list_b.len() {
+        return false;
+    }
+
+    // If exact match is true, and path list A does not have the same number of items as path list B, return
+    // "Does Not Match".
+    if exact_match && path_list_a.len() != path_list_b.len() {
+        return false;
+    }
+
+    // If exact match is false:
+    // Assert: the final item in path list A is the empty string.
+    // Remove the final item from path list A.
+    if !exact_match {
+        path_list_a.pop();
+    }
+
+    // For each piece A of path list A:
+    // Let piece B be the next item in path list B.
+    // Let decoded piece A be the percent-decoding of piece A.
+    // Let decoded piece B be the percent-decoding of piece B.
+    // If decoded piece A is not decoded piece B, return "Does Not Match".
+    // Return "Matches".
+    path_list_a
+        .iter()
+        .zip(path_list_b)
+        .all(|(piece_a, piece_b)| percent_decode_str(piece_a).eq(percent_decode_str(piece_b)))
+}
@@ -0,0 +1,57 @@
+/// Special-use domain names from https://www.iana.org/assignments/special-use-domain-names
+/// and the `.internal` top-level domain ICANN reserved for private use.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SpecialUseDomain {
+    /// "localhost" and its subdomains, which always resolve to a loopback address. RFC 6761.
+    Localhost,
+    /// ".test", for testing. RFC 6761.
+    Test,
+    /// ".invalid", which is guaranteed never to resolve. RFC 6761.
+    Invalid,
+    /// ".example", "example.com", "example.net" and "example.org", for documentation. RFC 6761.
+    Example,
+    /// ".local", resolved with Multicast DNS on the local link. RFC 6762.
+    Local,
+    /// ".onion", reachable only through Tor. RFC 7686.
+    Onion,
+    /// ".home.arpa", for residential home networks. RFC 8375.
+    HomeArpa,
+    /// ".internal", for private networks.
+    Internal,
+}
+
+/// The special-use domain the name is, or is a subdomain of. The comparison ignores ASCII case
+/// and a trailing dot.
+pub fn special_use_domain(domain: &str) -> Option<SpecialUseDomain> {
+    let domain = domain.strip_suffix('.').unwrap_or(domain);
+
+    let suffixes = [
+        ("localhost", SpecialUseDomain::Localhost),
+        ("test", SpecialUseDomain::Test),
+        ("invalid", SpecialUseDomain::Invalid),
+        ("example", SpecialUseDomain::Example),
+        ("example.com", SpecialUseDomain::Example),
+        ("example.net", SpecialUseDomain::Example),
+        ("example.org", SpecialUseDomain::Example),
+        ("local", SpecialUseDomain::Local),
+        ("onion", SpecialUseDomain::Onion),
+        ("home.arpa", SpecialUseDomain::HomeArpa),
+        ("internal", SpecialUseDomain::Internal),
+    ];
+
+    suffixes
+        .into_iter()
+        .find(|(suffix, _)| is_same_or_subdomain(domain, suffix))
+        .map(|(_, special_use)| special_use)
+}
+
+/// Returns true if the domain is the suffix, or ends with a U+002E (.) followed by the suffix.
+fn is_same_or_subdomain(domain: &str, suffix: &str) -> bool {
+    let Some(start) = domain.len().checked_sub(suffix.len()) else {
+        return false;
+    };
+
+    domain.is_char_boundary(start)
+        && domain[start..].eq_ignore_ascii_case(suffix)
+        && (start == 0 || domain[..start].ends_with('.'))
+}
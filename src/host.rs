@@ -0,0 +1,264 @@
+use crate::domain::{special_use_domain, SpecialUseDomain};
+use crate::ip::{special_purpose, SpecialPurpose};
+use crate::parser::{ends_with_a_number, parse_ipv4, parse_ipv6, parse_opaque_host};
+use crate::serializers::{serialize_ipv4, serialize_ipv6};
+use crate::uts46::{domain_to_ascii, IdnaError, Uts46Options};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use percent_encoding::percent_decode_str;
+
+/// https://url.spec.whatwg.org/#concept-host
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Host {
+    /// An ASCII domain, after domain to ASCII.
+    Domain(String),
+    Ipv4(Ipv4Addr),
+    Ipv6(Ipv6Addr),
+    /// An IPv6 address with an RFC 6874 zone identifier, only parsed when `ParseOptions::ipv6_zone_id` is set.
+    /// The zone identifier is kept as it was written, percent-encoded.
+    Ipv6WithZone(Ipv6Addr, String),
+    /// The host of a URL whose scheme is not special.
+    Opaque(String),
+}
+
+impl Host {
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self {
+            Host::Ipv4(address) => Some(IpAddr::V4(*address)),
+            Host::Ipv6(address) | Host::Ipv6WithZone(address, _) => Some(IpAddr::V6(*address)),
+            Host::Domain(_) | Host::Opaque(_) => None,
+        }
+    }
+
+    /// The special-purpose block of an IP address host, as `ip::special_purpose` classifies it.
+    /// Returns `None` for domains, opaque hosts, and addresses outside of every block.
+    pub fn special_purpose(&self) -> Option<SpecialPurpose> {
+        self.ip().and_then(special_purpose)
+    }
+
+    /// The special-use domain a domain host is, or is a subdomain of. Returns `None` for IP
+    /// addresses and opaque hosts.
+    pub fn special_use_domain(&self) -> Option<SpecialUseDomain> {
+        match self {
+            Host::Domain(domain) => special_use_domain(domain),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the host is a loopback address: in 127.0.0.0/8, as with 127.0.0.1, 0x7f.1 and
+    /// [::ffff:127.0.0.1], or ::1. "localhost" is a domain, and is not.
+    pub fn is_loopback(&self) -> bool {
+        match self.ip() {
+            Some(IpAddr::V4(address)) => address.is_loopback(),
+            Some(IpAddr::V6(address)) => {
+                address.is_loopback() || address.to_ipv4_mapped().is_some_and(|v4| v4.is_loopback())
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if the host is an IP address that most systems connect to this host: loopback
+    /// and unspecified addresses and 0.0.0.0/8, including in the IPv6 forms that embed them, such as
+    /// [::ffff:0.0.0.0] and [64:ff9b::7f00:1].
+    pub fn reaches_this_host(&self) -> bool {
+        matches!(
+            self.special_purpose(),
+            Some(
+                SpecialPurpose::Loopback
+                    | SpecialPurpose::Unspecified
+                    | SpecialPurpose::ThisNetwork
+            )
+        )
+    }
+}
+
+/// https://url.spec.whatwg.org/#concept-host-serializer
+impl core::fmt::Display for Host {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Host::Domain(domain) | Host::Opaque(domain) => f.write_str(domain),
+            Host::Ipv4(address) => f.write_str(&serialize_ipv4(*address)),
+            Host::Ipv6(address) => write!(f, "[{}]", serialize_ipv6(*address)),
+            Host::Ipv6WithZone(address, zone) => {
+                write!(f, "[{}%25{}]", serialize_ipv6(*address), zone)
+            }
+        }
+    }
+}
+
+impl From<Ipv4Addr> for Host {
+    fn from(address: Ipv4Addr) -> Self {
+        Host::Ipv4(address)
+    }
+}
+
+impl From<Ipv6Addr> for Host {
+    fn from(address: Ipv6Addr) -> Self {
+        Host::Ipv6(address)
+    }
+}
+
+impl From<IpAddr> for Host {
+    fn from(address: IpAddr) -> Self {
+        match address {
+            IpAddr::V4(address) => Host::Ipv4(address),
+            IpAddr::V6(address) => Host::Ipv6(address),
+        }
+    }
+}
+
+/// The port is dropped, and a non-zero IPv6 scope id becomes a numeric zone identifier.
+impl From<SocketAddr> for Host {
+    fn from(address: SocketAddr) -> Self {
+        match address {
+            SocketAddr::V6(address) if address.scope_id() != 0 => {
+                Host::Ipv6WithZone(*address.ip(), address.scope_id().to_string())
+            }
+            _ => Host::from(address.ip()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HostError {
+    /// An IPv6 address that is missing its closing U+005D (]) or is not valid.
+    InvalidIpv6,
+    /// An opaque host that contains a forbidden host code point.
+    ForbiddenHostCodePoint,
+    /// A domain that UTS #46 ToASCII rejects, with the errors of each label.
+    Idna(Vec<IdnaError>),
+    /// A domain that is empty after ToASCII.
+    EmptyHost,
+    /// A domain that contains a forbidden domain code point after ToASCII.
+    ForbiddenDomainCodePoint,
+    /// A domain that ends in a number but is not a valid IPv4 address.
+    InvalidIpv4,
+}
+
+/// https://www.rfc-editor.org/rfc/rfc6874#section-2
+///
+/// IP-literal = "[" ( IPv6address / IPv6addrz / IPvFuture  ) "]"
+/// ZoneID = 1*( unreserved / pct-encoded )
+/// IPv6addrz = IPv6address "%25" ZoneID
+pub(crate) fn parse_ipv6_with_zone_id(input: &str) -> Result<Host, HostError> {
+    let Some((address, zone)) = input.split_once("%25") else {
+        return parse_ipv6(input.to_string())
+            .map(Host::Ipv6)
+            .ok_or(HostError::InvalidIpv6);
+    };
+
+    let address = parse_ipv6(address.to_string()).ok_or(HostError::InvalidIpv6)?;
+
+    let bytes = zone.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => index += 1,
+            b'%' if bytes
+                .get(index + 1..index + 3)
+                .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) =>
+            {
+                index += 3
+            }
+            _ => return Err(HostError::InvalidIpv6),
+        }
+    }
+
+    if zone.is_empty() {
+        return Err(HostError::InvalidIpv6);
+    }
+
+    Ok(Host::Ipv6WithZone(address, zone.to_string()))
+}
+
+/// https://url.spec.whatwg.org/#host-parsing
+///
+/// Domains go through UTS #46 ToASCII with the given options. The URL parser uses the defaults.
+pub fn parse_host(
+    input: &str,
+    is_not_special: bool,
+    options: &Uts46Options,
+) -> Result<Host, HostError> {
+    parse_host_with_zone_id(input, is_not_special, options, false)
+}
+
+/// Host parsing, accepting IPv6 zone identifiers if `zone_id` is true.
+pub(crate) fn parse_host_with_zone_id(
+    input: &str,
+    is_not_special: bool,
+    options: &Uts46Options,
+    zone_id: bool,
+) -> Result<Host, HostError> {
+    // If input starts with U+005B ([), then:
+    if input.starts_with('[') {
+        // If input does not end with U+005D (]), validation error, return failure.
+        if !input.ends_with(']') {
+            return Err(HostError::InvalidIpv6);
+        }
+
+        if zone_id {
+            return parse_ipv6_with_zone_id(&input[1..input.len() - 1]);
+        }
+
+        // Return the result of IPv6 parsing input with its leading U+005B ([) and trailing U+005D (]) removed.
+        return parse_ipv6(input[1..input.len() - 1].to_string())
+            .map(Host::Ipv6)
+            .ok_or(HostError::InvalidIpv6);
+    }
+
+    // If isNotSpecial is true, then return the result of opaque-host parsing input.
+    if is_not_special {
+        return parse_opaque_host(input)
+            .map(Host::Opaque)
+            .ok_or(HostError::ForbiddenHostCodePoint);
+    }
+
+    // Let domain be the result of running UTF-8 decode without BOM on the percent-decoding of input.
+    let domain = percent_decode_str(input).decode_utf8_lossy();
+
+    // Let asciiDomain be the result of running domain to ASCII with domain and false.
+    // If asciiDomain is failure, validation error, return failure.
+    let ascii_domain = domain_to_ascii(&domain, options).map_err(HostError::Idna)?;
+
+    if ascii_domain.is_empty() {
+        return Err(HostError::EmptyHost);
+    }
+
+    // If asciiDomain contains a forbidden domain code point, validation error, return failure.
+    let is_invalid_domain_char = |c| {
+        matches!(
+            c,
+            '\0'..='\u{001F}'
+                | ' '
+                | '#'
+                | '%'
+                | '/'
+                | ':'
+                | '<'
+                | '>'
+                | '?'
+                | '@'
+                | '['
+                | '\\'
+                | ']'
+                | '^'
+                | '\u{007F}'
+                | '|'
+        )
+    };
+
+    if ascii_domain.find(is_invalid_domain_char).is_some() {
+        return Err(HostError::ForbiddenDomainCodePoint);
+    }
+
+    // If asciiDomain ends in a number, then return the result of IPv4 parsing asciiDomain.
+    if ends_with_a_number(ascii_domain.as_str()) {
+        return parse_ipv4(ascii_domain)
+            .map(Host::Ipv4)
+            .ok_or(HostError::InvalidIpv4);
+    }
+
+    Ok(Host::Domain(ascii_domain))
+}
@@ -0,0 +1,254 @@
+use crate::host::{parse_host, Host};
+use crate::scheme::SchemeType;
+use crate::url::URL;
+use crate::uts46::Uts46Options;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+
+/// Why a `Strict-Transport-Security` header was ignored by `HstsStore::process_header`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HstsError {
+    /// The response was not received over a secure transport.
+    InsecureTransport,
+    /// The host of the response is not a domain. IP addresses are never Known HSTS Hosts.
+    NotDomain,
+    /// The header does not follow the grammar, has no max-age directive, or repeats a directive.
+    InvalidHeader,
+}
+
+/// A line of a preload list that could not be read. Lines are counted from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreloadError {
+    pub line: usize,
+}
+
+/// A Known HSTS Host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HstsEntry {
+    pub include_subdomains: bool,
+    /// Seconds since the Unix epoch after which the entry no longer applies. `None` for preloaded entries,
+    /// which never expire.
+    pub expires: Option<u64>,
+}
+
+/// The Known HSTS Hosts of RFC 6797. Times are seconds since the Unix epoch, passed in by the caller.
+///
+/// https://www.rfc-editor.org/rfc/rfc6797
+#[derive(Clone, Debug, Default)]
+pub struct HstsStore {
+    /// Keyed by the domain as `parse_host` returns it, without a trailing dot.
+    entries: BTreeMap<String, HstsEntry>,
+}
+
+impl HstsStore {
+    pub fn new() -> Self {
+        HstsStore::default()
+    }
+
+    /// Adds the entries of a preload list. Each line holds a domain, optionally followed by "includeSubDomains";
+    /// empty lines and lines starting with "#" are skipped.
+    pub fn load_preload_list(&mut self, list: &str) -> Result<(), PreloadError> {
+        for (index, line) in list.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = PreloadError { line: index + 1 };
+            let mut parts = line.split_whitespace();
+            let domain = parts
+                .next()
+                .and_then(canonical_domain)
+                .ok_or(error.clone())?;
+
+            let include_subdomains = match parts.next() {
+                None => false,
+                Some(directive) if directive.eq_ignore_ascii_case("includeSubDomains") => true,
+                Some(_) => return Err(error),
+            };
+
+            if parts.next().is_some() {
+                return Err(error);
+            }
+
+            self.entries.insert(
+                domain,
+                HstsEntry {
+                    include_subdomains,
+                    expires: None,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// https://www.rfc-editor.org/rfc/rfc6797#section-8.1
+    ///
+    /// Notes the host of `url` as a Known HSTS Host, updates it, or forgets it if max-age is 0. Preloaded
+    /// entries are left as they are.
+    pub fn process_header(&mut self, url: &URL, value: &str, now: u64) -> Result<(), HstsError> {
+        // If an HTTP response, received over a secure transport, includes an STS header field, conforming to the
+        // grammar specified in Section 6.1, and there are no underlying secure transport errors or warnings, the
+        // UA MUST note the host as a Known HSTS Host.
+        if !matches!(url.scheme_type, SchemeType::Https | SchemeType::Wss) {
+            return Err(HstsError::InsecureTransport);
+        }
+
+        // If the substring matching the host production from the Request-URI is an IP-literal or IPv4address,
+        // the UA MUST NOT note this host as a Known HSTS Host.
+        let domain = match url.parsed_host() {
+            Some(Host::Domain(domain)) => domain.trim_end_matches('.').to_string(),
+            _ => return Err(HstsError::NotDomain),
+        };
+
+        let (max_age, include_subdomains) = parse_header(value).ok_or(HstsError::InvalidHeader)?;
+
+        if self
+            .entries
+            .get(&domain)
+            .is_some_and(|entry| entry.expires.is_none())
+        {
+            return Ok(());
+        }
+
+        // A max-age value of zero signals the UA to cease regarding the host as a Known HSTS Host.
+        if max_age == 0 {
+            self.entries.remove(&domain);
+        } else {
+            self.entries.insert(
+                domain,
+                HstsEntry {
+                    include_subdomains,
+                    expires: Some(now.saturating_add(max_age)),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// https://www.rfc-editor.org/rfc/rfc6797#section-8.2
+    ///
+    /// The entry that makes `domain` a Known HSTS Host: a congruent match, or else a superdomain match with
+    /// includeSubDomains.
+    pub fn find(&self, domain: &str, now: u64) -> Option<&HstsEntry> {
+        let domain = domain.trim_end_matches('.');
+        let is_current = |entry: &&HstsEntry| match entry.expires {
+            Some(expires) => expires > now,
+            None => true,
+        };
+
+        if let Some(entry) = self.entries.get(domain).filter(is_current) {
+            return Some(entry);
+        }
+
+        domain
+            .match_indices('.')
+            .map(|(index, _)| &domain[index + 1..])
+            .filter_map(|superdomain| self.entries.get(superdomain))
+            .filter(is_current)
+            .find(|entry| entry.include_subdomains)
+    }
+
+    /// https://www.rfc-editor.org/rfc/rfc6797#section-8.3
+    ///
+    /// Changes "http" to "https" and "ws" to "wss" if the host of `url` is a Known HSTS Host. Like the scheme
+    /// setter, a port that becomes the default port of the new scheme is removed, and port 80 becomes 443.
+    /// Returns true if the URL was changed.
+    pub fn upgrade(&self, url: &mut URL, now: u64) -> bool {
+        let (scheme, scheme_type) = match url.scheme_type {
+            SchemeType::Http => ("https", SchemeType::Https),
+            SchemeType::Ws => ("wss", SchemeType::Wss),
+            _ => return false,
+        };
+
+        let is_known = match url.parsed_host() {
+            Some(Host::Domain(domain)) => self.find(&domain, now).is_some(),
+            _ => false,
+        };
+
+        if !is_known {
+            return false;
+        }
+
+        url.scheme = scheme.to_string();
+        url.scheme_type = scheme_type;
+
+        // If the URI contains an explicit port component of "80", then the UA MUST convert the port component
+        // to be "443".
+        // If url’s port is url’s scheme’s default port, then set url’s port to null.
+        if url.port.as_deref() == Some("80")
+            || url.port.as_ref().and_then(|port| port.parse::<u16>().ok())
+                == scheme_type.default_port()
+        {
+            url.port = None;
+        }
+
+        true
+    }
+}
+
+/// The domain as the URL parser would serialize it, without a trailing dot.
+fn canonical_domain(input: &str) -> Option<String> {
+    match parse_host(input, false, &Uts46Options::default()) {
+        Ok(Host::Domain(domain)) => Some(domain.trim_end_matches('.').to_string()),
+        _ => None,
+    }
+}
+
+/// https://www.rfc-editor.org/rfc/rfc6797#section-6.1
+///
+/// Returns max-age and whether includeSubDomains is present.
+fn parse_header(value: &str) -> Option<(u64, bool)> {
+    let mut max_age = None;
+    let mut include_subdomains = false;
+
+    for directive in value.split(';') {
+        let directive = directive.trim_matches([' ', '\t']);
+
+        if directive.is_empty() {
+            continue;
+        }
+
+        let (name, value) = match directive.split_once('=') {
+            Some((name, value)) => (
+                name.trim_matches([' ', '\t']),
+                Some(value.trim_matches([' ', '\t'])),
+            ),
+            None => (directive, None),
+        };
+
+        // directive-value = token / quoted-string
+        let value = value.map(|value| {
+            value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value)
+        });
+
+        // All directives MUST appear only once in an STS header field. Directive names are case-insensitive.
+        if name.eq_ignore_ascii_case("max-age") {
+            // max-age-value = delta-seconds
+            let value = value
+                .filter(|value| !value.is_empty() && value.bytes().all(|c| c.is_ascii_digit()))?;
+
+            if max_age.is_some() {
+                return None;
+            }
+
+            max_age = Some(value.parse().unwrap_or(u64::MAX));
+        } else if name.eq_ignore_ascii_case("includeSubDomains") {
+            if include_subdomains || value.is_some() {
+                return None;
+            }
+
+            include_subdomains = true;
+        }
+    }
+
+    // The REQUIRED "max-age" directive specifies the number of seconds, after the reception of the STS header
+    // field, during which the UA regards the host as a Known HSTS Host.
+    Some((max_age?, include_subdomains))
+}
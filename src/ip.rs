@@ -0,0 +1,129 @@
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// The blocks of the IANA IPv4 and IPv6 Special-Purpose Address Registries, from RFC 6890 and the
+/// RFCs that updated it: https://www.iana.org/assignments/iana-ipv4-special-registry
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SpecialPurpose {
+    /// 0.0.0.0/32 and ::/128.
+    Unspecified,
+    /// 0.0.0.0/8, "this host on this network".
+    ThisNetwork,
+    /// 127.0.0.0/8 and ::1/128.
+    Loopback,
+    /// 10.0.0.0/8, 172.16.0.0/12, 192.168.0.0/16, the deprecated IPv6 site-local fec0::/10, and
+    /// the local-use IPv4/IPv6 translation prefix 64:ff9b:1::/48.
+    Private,
+    /// 100.64.0.0/10, used for carrier-grade NAT.
+    SharedAddressSpace,
+    /// 169.254.0.0/16 and fe80::/10.
+    LinkLocal,
+    /// fc00::/7.
+    UniqueLocal,
+    /// 192.0.0.0/24 and 2001::/23.
+    IetfProtocolAssignments,
+    /// 192.0.2.0/24, 198.51.100.0/24, 203.0.113.0/24, 2001:db8::/32 and 3fff::/20.
+    Documentation,
+    /// 198.18.0.0/15 and 2001:2::/48.
+    Benchmarking,
+    /// 100::/64.
+    DiscardOnly,
+    /// 2002::/16, 6to4 addresses. They are not classified by the IPv4 address they embed, which
+    /// is that of a 6to4 site rather than of the destination itself.
+    SixToFour,
+    /// 224.0.0.0/4 and ff00::/8.
+    Multicast,
+    /// 255.255.255.255/32.
+    Broadcast,
+    /// 240.0.0.0/4, the deprecated 6to4 relay anycast 192.88.99.0/24, and the rest of ::/8.
+    Reserved,
+}
+
+/// The special-purpose block the address belongs to, or `None` if it is in none of them.
+///
+/// IPv6 addresses that embed an IPv4 address (IPv4-mapped, IPv4-translated and NAT64) are
+/// classified by the IPv4 address they embed, as that is where they lead. 6to4 addresses are
+/// `SpecialPurpose::SixToFour`.
+pub fn special_purpose(address: IpAddr) -> Option<SpecialPurpose> {
+    match canonical(address) {
+        IpAddr::V4(address) => ipv4_special_purpose(address),
+        IpAddr::V6(address) => ipv6_special_purpose(address),
+    }
+}
+
+/// The IPv4 address an IPv6 address embeds, if any, and the address itself otherwise. 6to4
+/// addresses are kept as they are, as `special_purpose` classifies them.
+///
+/// Different addresses can have the same canonical address, so only use this to deny access, never
+/// to allow it.
+pub fn canonical(address: IpAddr) -> IpAddr {
+    match address {
+        IpAddr::V6(v6) => embedded_ipv4(v6).map_or(address, IpAddr::V4),
+        IpAddr::V4(_) => address,
+    }
+}
+
+/// The IPv4 address of an IPv4-mapped (::ffff:0:0/96), IPv4-translated (::ffff:0:0:0/96) or
+/// NAT64 well-known prefix (64:ff9b::/96) address. The IPv4 address in a 6to4 (2002::/16) address
+/// is that of a 6to4 site rather than of the destination, so it is not returned.
+pub fn embedded_ipv4(address: Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = address.segments();
+    let octets = address.octets();
+
+    match segments {
+        [0, 0, 0, 0, 0, 0xffff, _, _]
+        | [0, 0, 0, 0, 0xffff, 0, _, _]
+        | [0x64, 0xff9b, 0, 0, 0, 0, _, _] => Some(Ipv4Addr::new(
+            octets[12], octets[13], octets[14], octets[15],
+        )),
+        _ => None,
+    }
+}
+
+fn ipv4_special_purpose(address: Ipv4Addr) -> Option<SpecialPurpose> {
+    let octets = address.octets();
+
+    let purpose = match octets {
+        [0, 0, 0, 0] => SpecialPurpose::Unspecified,
+        [0, ..] => SpecialPurpose::ThisNetwork,
+        [10, ..] => SpecialPurpose::Private,
+        [100, b, ..] if b & 0xc0 == 64 => SpecialPurpose::SharedAddressSpace,
+        [127, ..] => SpecialPurpose::Loopback,
+        [169, 254, ..] => SpecialPurpose::LinkLocal,
+        [172, b, ..] if b & 0xf0 == 16 => SpecialPurpose::Private,
+        [192, 0, 0, _] => SpecialPurpose::IetfProtocolAssignments,
+        [192, 0, 2, _] | [198, 51, 100, _] | [203, 0, 113, _] => SpecialPurpose::Documentation,
+        [192, 88, 99, _] => SpecialPurpose::Reserved,
+        [192, 168, ..] => SpecialPurpose::Private,
+        [198, b, ..] if b & 0xfe == 18 => SpecialPurpose::Benchmarking,
+        [a, ..] if a & 0xf0 == 224 => SpecialPurpose::Multicast,
+        [255, 255, 255, 255] => SpecialPurpose::Broadcast,
+        [a, ..] if a & 0xf0 == 240 => SpecialPurpose::Reserved,
+        _ => return None,
+    };
+
+    Some(purpose)
+}
+
+fn ipv6_special_purpose(address: Ipv6Addr) -> Option<SpecialPurpose> {
+    let segments = address.segments();
+
+    let purpose = match segments {
+        [0, 0, 0, 0, 0, 0, 0, 0] => SpecialPurpose::Unspecified,
+        [0, 0, 0, 0, 0, 0, 0, 1] => SpecialPurpose::Loopback,
+        [a, ..] if a & 0xff00 == 0 => SpecialPurpose::Reserved,
+        [0x64, 0xff9b, 1, ..] => SpecialPurpose::Private,
+        [0x100, 0, 0, 0, ..] => SpecialPurpose::DiscardOnly,
+        [0x2001, 2, 0, ..] => SpecialPurpose::Benchmarking,
+        [0x2001, 0xdb8, ..] => SpecialPurpose::Documentation,
+        [0x2001, b, ..] if b & 0xfe00 == 0 => SpecialPurpose::IetfProtocolAssignments,
+        [0x2002, ..] => SpecialPurpose::SixToFour,
+        [0x3fff, b, ..] if b & 0xf000 == 0 => SpecialPurpose::Documentation,
+        [a, ..] if a & 0xfe00 == 0xfc00 => SpecialPurpose::UniqueLocal,
+        [a, ..] if a & 0xffc0 == 0xfe80 => SpecialPurpose::LinkLocal,
+        [a, ..] if a & 0xffc0 == 0xfec0 => SpecialPurpose::Private,
+        [a, ..] if a & 0xff00 == 0xff00 => SpecialPurpose::Multicast,
+        _ => return None,
+    };
+
+    Some(purpose)
+}
@@ -1,8 +1,32 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod allowlist;
+pub mod batch;
+pub mod cookie;
+pub mod csp;
+pub mod domain;
 mod encode_sets;
+pub mod host;
+pub mod hsts;
+pub mod ip;
 pub mod machine;
+pub mod net;
+pub mod origin;
 mod parser;
 mod platform;
+pub mod policy;
+pub mod psl;
+pub mod redact;
+pub mod redirect;
+pub mod referrer;
+pub mod registry;
+pub mod scheme;
 mod serializers;
 pub mod state;
 mod string;
+pub mod trustworthy;
 pub mod url;
+pub mod url_ref;
+pub mod uts46;
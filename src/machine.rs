@@ -6,67 +6,224 @@ use crate::parser::parse_host;
 use crate::platform::{
     is_normalized_windows_drive_letter, is_windows_drive_letter, starts_with_windows_drive_letter,
 };
-use crate::state::{Code, State, SPECIAL_SCHEMES};
+use crate::scheme::SchemeType;
+use crate::state::{Code, State};
 use crate::string::{
     is_ascii_alphanumeric, is_ascii_digit, is_double_dot_path_segment, is_single_dot_path_segment,
 };
 use crate::url::URL;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use core::borrow::Borrow;
 use percent_encoding::{utf8_percent_encode, CONTROLS};
-use std::borrow::Borrow;
 
-pub struct URLStateMachine {
+pub struct URLStateMachine<'a> {
     buffer: String,
     at_sign_seen: bool,
     inside_brackets: bool,
     password_token_seen: bool,
     pointer: i32,
     pub failure: bool,
+    /// The limit that made parsing fail, if any.
+    pub limit_exceeded: Option<LimitExceeded>,
     state_override: bool,
     encoding_override: String,
-    is_special_url: bool,
     state: State,
-    base: Option<URL>,
+    base: Option<&'a URL>,
     pub url: URL,
     input: String,
+    validate_only: bool,
+    options: ParseOptions,
+    /// The number of bytes percent-encoding has added so far.
+    expansion: usize,
 }
 
-impl URLStateMachine {
+/// Parsing behavior beyond the URL Standard. Every option is off by default, which follows the standard.
+///
+/// Built with `ParseOptions::default()` and the methods named after each field, so that options can be added.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Accept IPv6 hosts with an RFC 6874 zone identifier, as in "http://[fe80::1%25eth0]/".
+    pub ipv6_zone_id: bool,
+    pub limits: ParseLimits,
+}
+
+/// Bounds on the resources a URL may make the parser use, for input from untrusted sources.
+/// `None` is unlimited, which is the default.
+///
+/// Built with `ParseLimits::default()` and the methods named after each field, so that limits can be added.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseLimits {
+    /// In bytes, before leading and trailing C0 controls and spaces are removed.
+    pub max_input_length: Option<usize>,
+    pub max_path_segments: Option<usize>,
+    /// In bytes of the host as written, checked before host parsing, and of the serialized host.
+    pub max_host_length: Option<usize>,
+    /// In bytes of each dot-separated part of the serialized host.
+    pub max_label_length: Option<usize>,
+    /// In bytes of the serialized query.
+    pub max_query_length: Option<usize>,
+    /// The number of bytes percent-encoding may add to the userinfo, path, query and fragment altogether.
+    pub max_percent_encoded_expansion: Option<usize>,
+}
+
+impl ParseOptions {
+    pub fn ipv6_zone_id(mut self, ipv6_zone_id: bool) -> Self {
+        self.ipv6_zone_id = ipv6_zone_id;
+        self
+    }
+
+    pub fn limits(mut self, limits: ParseLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl ParseLimits {
+    pub fn max_input_length(mut self, max: usize) -> Self {
+        self.max_input_length = Some(max);
+        self
+    }
+
+    pub fn max_path_segments(mut self, max: usize) -> Self {
+        self.max_path_segments = Some(max);
+        self
+    }
+
+    pub fn max_host_length(mut self, max: usize) -> Self {
+        self.max_host_length = Some(max);
+        self
+    }
+
+    pub fn max_label_length(mut self, max: usize) -> Self {
+        self.max_label_length = Some(max);
+        self
+    }
+
+    pub fn max_query_length(mut self, max: usize) -> Self {
+        self.max_query_length = Some(max);
+        self
+    }
+
+    pub fn max_percent_encoded_expansion(mut self, max: usize) -> Self {
+        self.max_percent_encoded_expansion = Some(max);
+        self
+    }
+}
+
+/// The limit of `ParseLimits` that a URL went over.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LimitExceeded {
+    InputLength,
+    PathSegments,
+    HostLength,
+    LabelLength,
+    QueryLength,
+    PercentEncodedExpansion,
+}
+
+/// Buffers that can be handed from one `URLStateMachine` run to the next.
+#[derive(Default)]
+pub(crate) struct Scratch {
+    buffer: String,
+    input: String,
+}
+
+impl<'a> URLStateMachine<'a> {
     pub fn new(
         input: &str,
-        base: Option<URL>,
+        base: Option<&'a URL>,
         encoding_override: Option<String>,
         state_override: Option<State>,
-    ) -> URLStateMachine {
+    ) -> URLStateMachine<'a> {
+        URLStateMachine::with_scratch(
+            input,
+            base,
+            encoding_override,
+            state_override,
+            Scratch::default(),
+            false,
+            ParseOptions::default(),
+        )
+    }
+
+    /// Like `new` without an encoding or state override, with the given options.
+    pub fn with_options(
+        input: &str,
+        base: Option<&'a URL>,
+        options: ParseOptions,
+    ) -> URLStateMachine<'a> {
+        URLStateMachine::with_scratch(input, base, None, None, Scratch::default(), false, options)
+    }
+
+    pub(crate) fn with_scratch(
+        input: &str,
+        base: Option<&'a URL>,
+        encoding_override: Option<String>,
+        state_override: Option<State>,
+        scratch: Scratch,
+        validate_only: bool,
+        options: ParseOptions,
+    ) -> URLStateMachine<'a> {
+        let Scratch {
+            mut buffer,
+            input: mut trimmed_input,
+        } = scratch;
+
+        buffer.clear();
+        trimmed_input.clear();
+
+        let is_too_long = options
+            .limits
+            .max_input_length
+            .is_some_and(|max| input.len() > max);
+
         // If input contains any leading or trailing C0 control or space, validation error.
         // If input contains any ASCII tab or newline, validation error.
-        let trimmed_input = input
-            .trim_matches(|c| c <= ' ')
-            .replace(|c| c == '\t' || c == '\n' || c == '\r', "")
-            .to_string();
+        if !is_too_long {
+            trimmed_input.extend(
+                input
+                    .trim_matches(|c| c <= ' ')
+                    .chars()
+                    .filter(|c| !matches!(c, '\t' | '\n' | '\r')),
+            );
+        }
 
         let mut machine = URLStateMachine {
-            buffer: "".to_string(),
+            buffer,
             at_sign_seen: false,
             inside_brackets: false,
             password_token_seen: false,
             pointer: 0,
             failure: false,
+            limit_exceeded: None,
             state_override: state_override.is_some(),
             encoding_override: encoding_override.unwrap_or_else(|| "utf-8".to_string()),
-            is_special_url: false,
             state: state_override.unwrap_or(State::SchemeStart),
             base,
             url: URL::new(),
-            input: trimmed_input.clone(),
+            input: trimmed_input,
+            validate_only,
+            options,
+            expansion: 0,
         };
 
-        let mut bytes: Vec<Option<u8>> = trimmed_input.bytes().map(|c| Some(c)).collect();
+        if is_too_long {
+            machine.failure = true;
+            machine.limit_exceeded = Some(LimitExceeded::InputLength);
 
-        // Traverse one more time for EOL character.
-        bytes.push(None);
+            return machine;
+        }
 
-        while machine.pointer < bytes.len() as i32 {
-            let byte = bytes[machine.pointer as usize];
+        // Traverse one more time for EOL character.
+        while machine.pointer <= machine.input.len() as i32 {
+            let byte = machine
+                .input
+                .as_bytes()
+                .get(machine.pointer as usize)
+                .copied();
 
             let result = match machine.state {
                 State::Authority => machine.authority_state(byte),
@@ -111,12 +268,117 @@ impl URLStateMachine {
 
         machine
     }
+
+    /// Returns the parsed URL, or `None` on failure, along with the buffers for the next run.
+    pub(crate) fn finish(self) -> (Option<URL>, Scratch) {
+        let url = if self.failure { None } else { Some(self.url) };
+        let scratch = Scratch {
+            buffer: self.buffer,
+            input: self.input,
+        };
+
+        (url, scratch)
+    }
+}
+
+/// https://url.spec.whatwg.org/#dom-url-canparse
+///
+/// Runs the state machine over input without building url’s components or percent-encoding them.
+pub fn can_parse(input: &str, base: Option<&str>) -> bool {
+    // If base is given, then:
+    let base = match base {
+        // Let parsedBase be the result of running the basic URL parser on base.
+        Some(base) => match URLStateMachine::new(base, None, None, None).finish().0 {
+            Some(url) => Some(url),
+            // If parsedBase is failure, then return false.
+            None => return false,
+        },
+        None => None,
+    };
+
+    // Let parsedURL be the result of running the basic URL parser on url with parsedBase.
+    // If parsedURL is failure, then return false.
+    let machine = URLStateMachine::with_scratch(
+        input,
+        base.as_ref(),
+        None,
+        None,
+        Scratch::default(),
+        true,
+        ParseOptions::default(),
+    );
+
+    !machine.failure
 }
 
-impl URLStateMachine {
+impl URLStateMachine<'_> {
+    /// Returns the code point that starts at pointer. The state machine walks input one byte at a time,
+    /// so this is `None` on the continuation bytes of a multi-byte code point and at the end of input.
+    fn code_point(&self) -> Option<char> {
+        self.input
+            .get(self.pointer as usize..)
+            .and_then(|remaining| remaining.chars().next())
+    }
+
+    fn is_special_url(&self) -> bool {
+        self.url.scheme_type.is_special()
+    }
+
+    /// Records that a limit was exceeded and returns failure.
+    fn exceed(&mut self, limit: LimitExceeded) -> Option<Code> {
+        self.limit_exceeded = Some(limit);
+
+        Some(Code::Failure)
+    }
+
+    /// Counts the bytes percent-encoding added to raw input, returning failure once there are too many.
+    fn add_expansion(&mut self, raw_length: usize, encoded_length: usize) -> Option<Code> {
+        self.expansion += encoded_length - raw_length;
+
+        match self.options.limits.max_percent_encoded_expansion {
+            Some(max) if self.expansion > max => {
+                self.exceed(LimitExceeded::PercentEncodedExpansion)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns failure if buffer is longer than the host length limit, so that hostile input does not go
+    /// through host parsing.
+    fn check_host_input(&mut self) -> Option<Code> {
+        if self
+            .options
+            .limits
+            .max_host_length
+            .is_some_and(|max| self.buffer.len() > max)
+        {
+            return self.exceed(LimitExceeded::HostLength);
+        }
+
+        None
+    }
+
+    /// Returns failure if url’s host is longer than the limits allow.
+    fn check_host_limits(&mut self) -> Option<Code> {
+        let limits = self.options.limits;
+        let host = self.url.host.as_deref().unwrap_or("");
+
+        if limits.max_host_length.is_some_and(|max| host.len() > max) {
+            return self.exceed(LimitExceeded::HostLength);
+        }
+
+        if let Some(max) = limits.max_label_length {
+            if host.split('.').any(|label| label.len() > max) {
+                return self.exceed(LimitExceeded::LabelLength);
+            }
+        }
+
+        None
+    }
+
     fn shorten_url(&mut self) {
         // If url’s scheme is "file", path’s size is 1, and path[0] is a normalized Windows drive letter, then return.
-        if self.url.scheme == *"file"
+        if self.url.scheme_type.is_file()
             && self.url.path.len() == 1
             && is_normalized_windows_drive_letter(self.url.path.first().unwrap())
         {
@@ -127,7 +389,7 @@ impl URLStateMachine {
     }
 }
 
-impl URLStateMachine {
+impl URLStateMachine<'_> {
     fn scheme_start_state(&mut self, code: Option<u8>) -> Option<Code> {
         // If c is an ASCII alpha, append c, lowercased, to buffer, and set state to scheme state.
         if is_ascii_alphanumeric(code) {
@@ -154,7 +416,8 @@ impl URLStateMachine {
         }
         // Otherwise, if c is U+003A (:), then:
         else if code == Some(58) {
-            let is_buffer_special = SPECIAL_SCHEMES.contains_key(self.buffer.as_str());
+            let buffer_type = SchemeType::from(self.buffer.as_str());
+            let is_buffer_special = buffer_type.is_special();
 
             // If state override is given, then:
             if self.state_override {
@@ -162,13 +425,13 @@ impl URLStateMachine {
                 // If url’s scheme is not a special scheme and buffer is a special scheme, then return.
                 // If url includes credentials or has a non-null port, and buffer is 'file', then return.
                 // If url’s scheme is 'file' and its host is an empty host, then return.
-                if (self.is_special_url && !is_buffer_special)
-                    || (!self.is_special_url && is_buffer_special)
+                if (self.is_special_url() && !is_buffer_special)
+                    || (!self.is_special_url() && is_buffer_special)
                     || ((self.url.username.len() > 0
                         || self.url.password.len() > 0
                         || self.url.port.is_some())
-                        && self.buffer == *"file")
-                    || (self.url.scheme == *"file"
+                        && buffer_type.is_file())
+                    || (self.url.scheme_type.is_file()
                         && self.url.host.is_some()
                         && self.url.host.as_ref().unwrap().len() == 0)
                 {
@@ -177,19 +440,16 @@ impl URLStateMachine {
             }
 
             // Set url’s scheme to buffer.
-            self.is_special_url = is_buffer_special;
-            self.url.scheme = self.buffer.clone();
+            if !self.validate_only {
+                self.url.scheme = self.buffer.clone();
+            }
+            self.url.scheme_type = buffer_type;
 
             // If state override is given, then:
             if self.state_override {
-                let port = SPECIAL_SCHEMES
-                    .get(self.url.scheme.as_str())
-                    .unwrap()
-                    .as_ref();
-
                 // If url’s port is url’s scheme’s default port, then set url’s port to null.
                 // TODO: Fix this
-                if let Some(port) = port {
+                if let Some(port) = self.url.scheme_type.default_port() {
                     if self.url.port == Some(port.to_string()) {
                         self.url.port = None;
                         return Some(Code::Exit);
@@ -201,30 +461,31 @@ impl URLStateMachine {
             self.buffer = "".to_string();
 
             // If url’s scheme is "file", then:
-            if self.url.scheme == *"file" {
+            if self.url.scheme_type.is_file() {
                 // Set state to file state.
                 self.state = State::File;
             }
             // Otherwise, if url is special, base is non-null, and base’s scheme is url’s scheme:
-            else if self.is_special_url
+            else if self.is_special_url()
                 && self.base.is_some()
-                && self.base.as_ref().unwrap().scheme == self.url.scheme
+                && self.base.as_ref().unwrap().scheme_type == self.url.scheme_type
             {
                 // Set state to special relative or authority state.
                 self.state = State::SpecialRelativeOrAuthority;
             }
             // Otherwise, if url is special, set state to special authority slashes state.
-            else if self.is_special_url {
+            else if self.is_special_url() {
                 self.state = State::SpecialAuthoritySlashes;
             }
             // Otherwise, if remaining starts with an U+002F (/), set state to path or authority state and increase pointer by 1.
-            else if self.input.chars().nth(self.pointer as usize + 1) == Some('/') {
+            else if self.input.as_bytes().get(self.pointer as usize + 1) == Some(&b'/') {
                 self.state = State::PathOrAuthority;
                 self.pointer += 1;
             }
             // Otherwise, set url’s path to the empty string and set state to opaque path state.
             else {
-                self.url.path = vec![];
+                self.url.path = vec!["".to_string()];
+                self.url.has_opaque_path = true;
                 self.state = State::OpaquePath;
             }
         }
@@ -245,7 +506,7 @@ impl URLStateMachine {
 
     fn host_state(&mut self, code: Option<u8>) -> Option<Code> {
         // If state override is given and url’s scheme is "file", then decrease pointer by 1 and set state to file host state.
-        if self.state_override && self.url.scheme == *"file" {
+        if self.state_override && self.url.scheme_type.is_file() {
             self.pointer -= 1;
             self.state = State::FileHost;
         }
@@ -260,7 +521,11 @@ impl URLStateMachine {
             // TODO: Implement this by changing state_override type from bool to Option<State>
 
             // Let host be the result of host parsing buffer with url is not special.
-            let host = parse_host(self.buffer.clone(), !self.is_special_url);
+            if let Some(code) = self.check_host_input() {
+                return Some(code);
+            }
+
+            let host = parse_host(self.buffer.clone(), !self.is_special_url(), &self.options);
 
             // If host is failure, then return failure.
             if host.is_empty() {
@@ -269,6 +534,11 @@ impl URLStateMachine {
 
             // Set url’s host to host, buffer to the empty string, and state to port state.
             self.url.host = Some(host);
+
+            if let Some(code) = self.check_host_limits() {
+                return Some(code);
+            }
+
             self.buffer = "".to_string();
             self.state = State::Port;
         }
@@ -279,13 +549,13 @@ impl URLStateMachine {
             || code == Some(47)
             || code == Some(63)
             || code == Some(35)
-            || (self.is_special_url && code == Some(92))
+            || (self.is_special_url() && code == Some(92))
         {
             // then decrease pointer by 1, and then:
             self.pointer -= 1;
 
             // If url is special and buffer is the empty string, validation error, return failure.
-            if self.is_special_url && self.buffer.is_empty() {
+            if self.is_special_url() && self.buffer.is_empty() {
                 return Some(Code::Failure);
             }
             // Otherwise, if state override is given, buffer is the empty string, and either url includes credentials or url’s port is non-null, return.
@@ -298,7 +568,11 @@ impl URLStateMachine {
                 return Some(Code::Exit);
             }
 
-            let host = parse_host(self.buffer.clone(), !self.is_special_url);
+            if let Some(code) = self.check_host_input() {
+                return Some(code);
+            }
+
+            let host = parse_host(self.buffer.clone(), !self.is_special_url(), &self.options);
 
             if host.is_empty() {
                 return Some(Code::Failure);
@@ -306,6 +580,11 @@ impl URLStateMachine {
 
             // Set url’s host to host, buffer to the empty string, and state to path start state.
             self.url.host = Some(host);
+
+            if let Some(code) = self.check_host_limits() {
+                return Some(code);
+            }
+
             self.buffer = "".to_string();
             self.state = State::PathStart;
 
@@ -323,7 +602,10 @@ impl URLStateMachine {
                 self.inside_brackets = false;
             }
 
-            self.buffer += (code as char).to_string().as_str();
+            // Append c to buffer.
+            if let Some(c) = self.code_point() {
+                self.buffer.push(c);
+            }
         }
 
         None
@@ -331,25 +613,25 @@ impl URLStateMachine {
 
     fn no_scheme_state(&mut self, code: Option<u8>) -> Option<Code> {
         // If base is null, or base has an opaque path and c is not U+0023 (#), validation error, return failure.
-        // TODO: Handle opaque path
-        if self.base.is_none() || code != Some(35) {
-            return Some(Code::Failure);
-        }
-
-        let base = self.base.as_ref().unwrap();
+        let base = match self.base {
+            Some(base) if !base.has_opaque_path || code == Some(35) => base,
+            _ => return Some(Code::Failure),
+        };
 
         // Otherwise, if base has an opaque path and c is U+0023 (#), set url’s scheme to base’s scheme,
         // url’s path to base’s path, url’s query to base’s query, url’s fragment to the empty string,
         // and set state to fragment state.
-        if code == Some(35) {
-            self.is_special_url = SPECIAL_SCHEMES.contains_key(base.scheme.as_str());
+        if base.has_opaque_path {
             self.url.scheme = base.scheme.clone();
+            self.url.scheme_type = base.scheme_type;
             self.url.path = base.path.clone();
+            self.url.has_opaque_path = true;
             self.url.query = base.query.clone();
             self.url.fragment = Some("".to_string());
+            self.state = State::Fragment;
         }
         // Otherwise, if base’s scheme is not 'file', set state to relative state and decrease pointer by 1.
-        else if base.scheme != "file".to_lowercase() {
+        else if !base.scheme_type.is_file() {
             self.state = State::Relative;
             self.pointer -= 1;
         }
@@ -372,30 +654,40 @@ impl URLStateMachine {
 
             self.at_sign_seen = true;
 
+            let mut raw_length = 0;
+            let mut encoded_length = 0;
+
             // For each codePoint in buffer:
-            for code_point in self.buffer.bytes() {
+            for code_point in self.buffer.chars().filter(|_| !self.validate_only) {
                 // If codePoint is U+003A (:) and passwordTokenSeen is false, then set passwordTokenSeen to true and continue.
-                if code_point == 58 && !self.password_token_seen {
+                if code_point == ':' && !self.password_token_seen {
                     self.password_token_seen = true;
                     continue;
                 }
 
                 // Let encodedCodePoints be the result of running UTF-8 percent-encode codePoint using the userinfo percent-encode set.
-                let input = self.input.chars().nth(self.pointer as usize).unwrap();
+                let mut bytes = [0; 4];
+                let encoded_code_points = utf8_percent_encode(
+                    code_point.encode_utf8(&mut bytes),
+                    USER_INFO_PERCENT_ENCODE_SET,
+                );
 
-                let encoded_code_points =
-                    utf8_percent_encode(input.to_string().as_str(), USER_INFO_PERCENT_ENCODE_SET)
-                        .to_string();
+                raw_length += code_point.len_utf8();
+                encoded_length += encoded_code_points.clone().map(str::len).sum::<usize>();
 
                 // If passwordTokenSeen is true, then append encodedCodePoints to url’s password.
                 if self.password_token_seen {
-                    self.url.password += encoded_code_points.as_str();
+                    self.url.password.extend(encoded_code_points);
                 } else {
                     // Otherwise, append encodedCodePoints to url’s username.
-                    self.url.username += encoded_code_points.as_str();
+                    self.url.username.extend(encoded_code_points);
                 }
             }
 
+            if let Some(code) = self.add_expansion(raw_length, encoded_length) {
+                return Some(code);
+            }
+
             self.buffer = "".to_string()
         }
         // Otherwise, if one of the following is true:
@@ -405,10 +697,10 @@ impl URLStateMachine {
             || code == Some(47)
             || code == Some(63)
             || code == Some(35)
-            || (self.is_special_url && code == Some(92))
+            || (self.is_special_url() && code == Some(92))
         {
             // If atSignSeen is true and buffer is the empty string, validation error, return failure.
-            if self.at_sign_seen && self.buffer.len() == 1 {
+            if self.at_sign_seen && self.buffer.is_empty() {
                 return Some(Code::Failure);
             }
 
@@ -418,7 +710,7 @@ impl URLStateMachine {
             self.state = State::Host;
         }
         // Otherwise, append c to buffer.
-        else if let Some(c) = self.input.chars().nth(self.pointer as usize) {
+        else if let Some(c) = self.code_point() {
             self.buffer += c.to_string().as_str();
         }
 
@@ -427,10 +719,15 @@ impl URLStateMachine {
 
     fn fragment_state(&mut self, code: Option<u8>) -> Option<Code> {
         // If c is not the EOF code point, then:
-        if let Some(_code) = code {
+        if code.is_some() && !self.validate_only {
             let fragment = &self.input[self.pointer as usize..];
-            self.url.fragment =
-                Some(utf8_percent_encode(fragment, FRAGMENT_PERCENT_ENCODE_SET).to_string());
+            let encoded = utf8_percent_encode(fragment, FRAGMENT_PERCENT_ENCODE_SET).to_string();
+
+            if let Some(code) = self.add_expansion(fragment.len(), encoded.len()) {
+                return Some(code);
+            }
+
+            self.url.fragment = Some(encoded);
         }
 
         Some(Code::Exit)
@@ -450,7 +747,7 @@ impl URLStateMachine {
         self.state = State::SpecialAuthorityIgnoreSlashes;
 
         // If c is U+002F (/) and remaining starts with U+002F (/),
-        if code == Some(47) && self.input.chars().nth(self.pointer as usize + 1) == Some('/') {
+        if code == Some(47) && self.input.as_bytes().get(self.pointer as usize + 1) == Some(&b'/') {
             // then set state to special authority ignore slashes state and increase pointer by 1.
             self.pointer += 1;
         } else {
@@ -464,7 +761,7 @@ impl URLStateMachine {
     fn special_relative_or_authority_state(&mut self, code: Option<u8>) -> Option<Code> {
         // If c is U+002F (/) and remaining starts with U+002F (/),
         // then set state to special authority ignore slashes state and increase pointer by 1.
-        if code == Some(47) && self.input.chars().nth(self.pointer as usize + 1) == Some('/') {
+        if code == Some(47) && self.input.as_bytes().get(self.pointer as usize + 1) == Some(&b'/') {
             self.state = State::SpecialAuthorityIgnoreSlashes;
             self.pointer += 1;
         }
@@ -500,14 +797,22 @@ impl URLStateMachine {
             }
             Some(35) => {
                 // Otherwise, if c is U+0023 (#), then set url’s fragment to the empty string and state to fragment state.
+                self.url.fragment = Some("".to_string());
                 self.state = State::Fragment;
             }
             _ => {
                 // If c is not the EOF code point, UTF-8 percent-encode c using the C0 control percent-encode set and append the result to url’s path.
-                if let Some(c) = self.input.chars().nth(self.pointer as usize) {
-                    self.url
-                        .path
-                        .push(utf8_percent_encode(c.to_string().as_str(), CONTROLS).to_string());
+                if let Some(c) = self.code_point().filter(|_| !self.validate_only) {
+                    let encoded =
+                        utf8_percent_encode(c.encode_utf8(&mut [0; 4]), CONTROLS).to_string();
+
+                    if let Some(code) = self.add_expansion(c.len_utf8(), encoded.len()) {
+                        return Some(code);
+                    }
+
+                    if let Some(path) = self.url.path.last_mut() {
+                        *path += encoded.as_str();
+                    }
                 }
             }
         }
@@ -519,24 +824,28 @@ impl URLStateMachine {
         // Set url’s scheme to base’s scheme.
         let base = self.base.as_ref().unwrap();
 
-        self.is_special_url = SPECIAL_SCHEMES.contains_key(base.scheme.as_str());
-        self.url.scheme = base.scheme.clone();
+        if !self.validate_only {
+            self.url.scheme = base.scheme.clone();
+        }
+        self.url.scheme_type = base.scheme_type;
 
         // If c is U+002F (/), then set state to relative slash state.
         // Otherwise, if url is special and c is U+005C (\), validation error, set state to relative slash state.
-        if code == Some(47) || (self.is_special_url && code == Some(92)) {
+        if code == Some(47) || (self.is_special_url() && code == Some(92)) {
             self.state = State::RelativeSlash;
         }
         // Otherwise:
         else {
             // Set url’s username to base’s username, url’s password to base’s password, url’s host to base’s host,
             // url’s port to base’s port, url’s path to a clone of base’s path, and url’s query to base’s query.
-            self.url.username = base.username.clone();
-            self.url.password = base.password.clone();
-            self.url.host = base.host.clone();
-            self.url.port = base.port.clone();
-            self.url.path = base.path.clone();
-            self.url.query = base.query.clone();
+            if !self.validate_only {
+                self.url.username = base.username.clone();
+                self.url.password = base.password.clone();
+                self.url.host = base.host.clone();
+                self.url.port = base.port.clone();
+                self.url.path = base.path.clone();
+                self.url.query = base.query.clone();
+            }
 
             // If c is U+003F (?), then set url’s query to the empty string, and state to query state.
             if code == Some(63) {
@@ -562,7 +871,7 @@ impl URLStateMachine {
 
     fn relative_slash_state(&mut self, code: Option<u8>) -> Option<Code> {
         // If url is special and c is U+002F (/) or U+005C (\), then:
-        if (self.is_special_url && code == Some(47)) || code == Some(92) {
+        if (self.is_special_url() && code == Some(47)) || code == Some(92) {
             // Set state to special authority ignore slashes state.
             self.state = State::SpecialAuthorityIgnoreSlashes
         }
@@ -573,11 +882,13 @@ impl URLStateMachine {
         // Otherwise, set url’s username to base’s username, url’s password to base’s password, url’s host to base’s host,
         // url’s port to base’s port, state to path state, and then, decrease pointer by 1.
         else {
-            let base = self.base.as_ref().unwrap();
-            self.url.username = base.username.clone();
-            self.url.password = base.password.clone();
-            self.url.host = base.host.clone();
-            self.url.port = base.port.clone();
+            if !self.validate_only {
+                let base = self.base.as_ref().unwrap();
+                self.url.username = base.username.clone();
+                self.url.password = base.password.clone();
+                self.url.host = base.host.clone();
+                self.url.port = base.port.clone();
+            }
             self.state = State::Path;
             self.pointer -= 1;
         }
@@ -587,7 +898,7 @@ impl URLStateMachine {
 
     fn path_start_state(&mut self, code: Option<u8>) -> Option<Code> {
         // If url is special, then:
-        if self.is_special_url {
+        if self.is_special_url() {
             // Set state to path state.
             self.state = State::Path;
 
@@ -603,6 +914,7 @@ impl URLStateMachine {
         }
         // Otherwise, if state override is not given and c is U+0023 (#), set url’s fragment to the empty string and state to fragment state.
         else if !self.state_override && code == Some(35) {
+            self.url.fragment = Some("".to_string());
             self.state = State::Fragment;
         }
         // Otherwise, if c is not the EOF code point:
@@ -636,7 +948,7 @@ impl URLStateMachine {
             || code == Some(47)
             || code == Some(63)
             || code == Some(35)
-            || (self.is_special_url && code == Some(92))
+            || (self.is_special_url() && code == Some(92))
             || self.state_override
         {
             // If buffer is not the empty string, then:
@@ -649,24 +961,14 @@ impl URLStateMachine {
                     return Some(Code::Failure);
                 }
 
-                let port = port_value.unwrap().to_string();
+                let port = port_value.unwrap();
 
                 // Set url’s port to null, if port is url’s scheme’s default port; otherwise to port.
-                if let Some(default_port_unwrapped) = SPECIAL_SCHEMES.get(self.url.scheme.as_str())
-                {
-                    if default_port_unwrapped.is_some() {
-                        let default_port = default_port_unwrapped.as_ref().unwrap().to_string();
-                        self.url.port = if port == default_port {
-                            None
-                        } else {
-                            Some(port)
-                        };
-                    } else {
-                        self.url.port = Some(port);
-                    }
+                self.url.port = if Some(port) == self.url.scheme_type.default_port() {
+                    None
                 } else {
-                    self.url.port = Some(port);
-                }
+                    Some(port.to_string())
+                };
 
                 // Set buffer to the empty string.
                 self.buffer = "".to_string();
@@ -694,7 +996,9 @@ impl URLStateMachine {
         // - url’s scheme is "ws" or "wss"
         // then set encoding to UTF-8.
         if self.encoding_override != "utf-8"
-            && (!self.is_special_url || self.url.scheme == *"ws" || self.url.scheme == *"wss")
+            && (!self.is_special_url()
+                || self.url.scheme_type == SchemeType::Ws
+                || self.url.scheme_type == SchemeType::Wss)
         {
             self.encoding_override = "utf-8".to_string();
         }
@@ -704,20 +1008,32 @@ impl URLStateMachine {
         // - c is the EOF code point
         if (!self.state_override && code == Some(35)) || code.is_none() {
             // Let queryPercentEncodeSet be the special-query percent-encode set if url is special; otherwise the query percent-encode set.
-            let encoding_set = if self.is_special_url {
+            let encoding_set = if self.is_special_url() {
                 SPECIAL_QUERY_PERCENT_ENCODE_SET
             } else {
                 QUERY_PERCENT_ENCODE_SET
             };
 
             // Percent-encode after encoding, with encoding, buffer, and queryPercentEncodeSet, and append the result to url’s query.
-            if let Some(query) = self.url.query.clone() {
-                self.url.query = Some(
-                    query
-                        + utf8_percent_encode(self.buffer.as_str(), encoding_set)
-                            .to_string()
-                            .borrow(),
-                );
+            if let Some(query) = self.url.query.clone().filter(|_| !self.validate_only) {
+                let encoded = utf8_percent_encode(self.buffer.as_str(), encoding_set).to_string();
+
+                if let Some(code) = self.add_expansion(self.buffer.len(), encoded.len()) {
+                    return Some(code);
+                }
+
+                let query = query + encoded.borrow();
+
+                if self
+                    .options
+                    .limits
+                    .max_query_length
+                    .is_some_and(|max| query.len() > max)
+                {
+                    return self.exceed(LimitExceeded::QueryLength);
+                }
+
+                self.url.query = Some(query);
             }
 
             // Set buffer to the empty string.
@@ -725,12 +1041,23 @@ impl URLStateMachine {
 
             // If c is U+0023 (#), then set url’s fragment to the empty string and state to fragment state.
             if code == Some(35) {
+                self.url.fragment = Some("".to_string());
                 self.state = State::Fragment;
             }
         }
         // Otherwise, if c is not the EOF code point: Append c to buffer
-        else if let Some(c) = self.input.chars().nth(self.pointer as usize) {
-            self.buffer += c.to_string().as_str()
+        else if let Some(c) = self.code_point().filter(|_| !self.validate_only) {
+            self.buffer += c.to_string().as_str();
+
+            // The query is at least as long as buffer, so there is no need to wait for the end of it.
+            if self
+                .options
+                .limits
+                .max_query_length
+                .is_some_and(|max| self.buffer.len() > max)
+            {
+                return self.exceed(LimitExceeded::QueryLength);
+            }
         }
 
         None
@@ -743,7 +1070,7 @@ impl URLStateMachine {
         // - state override is not given and c is U+003F (?) or U+0023 (#)
         if code.is_none()
             || code == Some(47)
-            || (self.is_special_url && code == Some(92))
+            || (self.is_special_url() && code == Some(92))
             || (!self.state_override && (code == Some(63)) || code == Some(35))
         {
             // If buffer is a double-dot path segment, then:
@@ -752,7 +1079,7 @@ impl URLStateMachine {
                 self.shorten_url();
 
                 // If neither c is U+002F (/), nor url is special and c is U+005C (\), append the empty string to url’s path.
-                if code != Some(47) && !(self.is_special_url && code == Some(92)) {
+                if code != Some(47) && !(self.is_special_url() && code == Some(92)) {
                     self.url.path.push("".to_string());
                 }
             }
@@ -760,15 +1087,15 @@ impl URLStateMachine {
             // nor url is special and c is U+005C (\), append the empty string to url’s path.
             else if is_single_dot_path_segment(self.buffer.as_str())
                 && code != Some(47)
-                && !(self.is_special_url && code == Some(92))
+                && !(self.is_special_url() && code == Some(92))
             {
                 self.url.path.push("".to_string());
             }
             // Otherwise, if buffer is not a single-dot path segment, then:
-            else if !is_single_dot_path_segment(self.buffer.as_str()) {
+            else if !self.validate_only && !is_single_dot_path_segment(self.buffer.as_str()) {
                 // If url’s scheme is "file", url’s path is empty, and buffer is a Windows drive letter,
                 // then replace the second code point in buffer with U+003A (:).
-                if self.url.scheme == *"file"
+                if self.url.scheme_type.is_file()
                     && self.url.path.is_empty()
                     && is_windows_drive_letter(self.buffer.as_str())
                 {
@@ -779,6 +1106,15 @@ impl URLStateMachine {
                 self.url.path.push(self.buffer.clone());
             }
 
+            if self
+                .options
+                .limits
+                .max_path_segments
+                .is_some_and(|max| self.url.path.len() > max)
+            {
+                return self.exceed(LimitExceeded::PathSegments);
+            }
+
             // Set buffer to the empty string.
             self.buffer = "".to_string();
 
@@ -793,10 +1129,15 @@ impl URLStateMachine {
             }
         }
         // Otherwise run these steps:
-        else if let Some(c) = self.input.chars().nth(self.pointer as usize) {
-            self.buffer += utf8_percent_encode(c.to_string().as_str(), PATH_PERCENT_ENCODE_SET)
-                .to_string()
-                .as_str();
+        else if let Some(c) = self.code_point().filter(|_| !self.validate_only) {
+            let encoded = utf8_percent_encode(c.encode_utf8(&mut [0; 4]), PATH_PERCENT_ENCODE_SET)
+                .to_string();
+
+            if let Some(code) = self.add_expansion(c.len_utf8(), encoded.len()) {
+                return Some(code);
+            }
+
+            self.buffer += encoded.as_str();
         }
 
         None
@@ -810,8 +1151,8 @@ impl URLStateMachine {
         // Otherwise:
         else {
             // If base is non-null and base’s scheme is "file", then:
-            if let Some(base) = self.base.as_ref() {
-                if base.scheme == *"file" {
+            if let Some(base) = self.base.filter(|_| !self.validate_only) {
+                if base.scheme_type.is_file() {
                     // Set url’s host to base’s host.
                     self.url.host = base.host.clone();
 
@@ -836,8 +1177,10 @@ impl URLStateMachine {
 
     fn file_state(&mut self, code: Option<u8>) -> Option<Code> {
         // Set url's scheme to "file".
-        self.url.scheme = "file".to_string();
-        self.is_special_url = true;
+        if !self.validate_only {
+            self.url.scheme = "file".to_string();
+        }
+        self.url.scheme_type = SchemeType::File;
 
         // Set url’s host to the empty string.
         self.url.host = Some("".to_string());
@@ -848,13 +1191,15 @@ impl URLStateMachine {
             self.state = State::FileSlash;
         }
         // Otherwise, if base is non-null and base’s scheme is "file":
-        else if self.base.is_some() && self.base.as_ref().unwrap().scheme == *"file" {
+        else if self.base.is_some() && self.base.as_ref().unwrap().scheme_type.is_file() {
             let base = self.base.as_ref().unwrap();
 
             // Set url’s host to base’s host, url’s path to a clone of base’s path, and url’s query to base’s query.
-            self.url.host = base.host.clone();
-            self.url.path = base.path.clone();
-            self.url.query = base.query.clone();
+            if !self.validate_only {
+                self.url.host = base.host.clone();
+                self.url.path = base.path.clone();
+                self.url.query = base.query.clone();
+            }
 
             // If c is U+003F (?), then set url’s query to the empty string and state to query state.
             if code == Some(63) {
@@ -896,11 +1241,8 @@ impl URLStateMachine {
     }
 
     fn file_host_state(&mut self, code: Option<u8>) -> Option<Code> {
-        if let Some(wrapped_code) = code {
-            self.buffer += (wrapped_code as char).to_string().as_str()
-        }
         // If c is the EOF code point, U+002F (/), U+005C (\), U+003F (?), or U+0023 (#), then decrease pointer by 1 and then:
-        else if code.is_none()
+        if code.is_none()
             || code == Some(47)
             || code == Some(92)
             || code == Some(63)
@@ -928,7 +1270,12 @@ impl URLStateMachine {
             // Otherwise, run these steps:
             else {
                 // Let host be the result of host parsing buffer with url is not special.
-                let mut host = parse_host(self.buffer.clone(), !self.is_special_url);
+                if let Some(code) = self.check_host_input() {
+                    return Some(code);
+                }
+
+                let mut host =
+                    parse_host(self.buffer.clone(), !self.is_special_url(), &self.options);
 
                 // If host is failure, then return failure.
                 if host.is_empty() {
@@ -943,6 +1290,10 @@ impl URLStateMachine {
                 // Set url’s host to host.
                 self.url.host = Some(host);
 
+                if let Some(code) = self.check_host_limits() {
+                    return Some(code);
+                }
+
                 // If state override is given, then return.
                 if self.state_override {
                     return Some(Code::Exit);
@@ -953,6 +1304,10 @@ impl URLStateMachine {
                 self.state = State::PathStart;
             }
         }
+        // Otherwise, append c to buffer.
+        else if let Some(c) = self.code_point() {
+            self.buffer.push(c);
+        }
 
         None
     }
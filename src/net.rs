@@ -0,0 +1,39 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::net::SocketAddr;
+
+/// Looks up the addresses of a domain, for `URL::socket_addrs`.
+pub trait Resolver {
+    type Error;
+
+    fn resolve(&self, domain: &str, port: u16) -> Result<Vec<SocketAddr>, Self::Error>;
+}
+
+/// Resolves domains with the system resolver, through `std::net::ToSocketAddrs`.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemResolver;
+
+#[cfg(feature = "std")]
+impl Resolver for SystemResolver {
+    type Error = std::io::Error;
+
+    fn resolve(&self, domain: &str, port: u16) -> Result<Vec<SocketAddr>, Self::Error> {
+        std::net::ToSocketAddrs::to_socket_addrs(&(domain, port)).map(Iterator::collect)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SocketAddrsError<E> {
+    /// The URL has no host, or an empty one.
+    MissingHost,
+    /// The URL has no port, and its scheme has no default port.
+    MissingPort,
+    /// The host of a URL whose scheme is not special is not known to name a network address.
+    OpaqueHost,
+    /// The IPv6 zone identifier is a name, like "eth0", rather than a numeric scope id. Mapping
+    /// names to scope ids needs the system's interfaces.
+    UnsupportedZone(String),
+    /// The resolver failed to look up the domain.
+    Resolve(E),
+}
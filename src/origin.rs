@@ -0,0 +1,179 @@
+use crate::host::Host;
+use crate::psl::PublicSuffixList;
+use crate::trustworthy::TrustworthyOrigins;
+use alloc::string::String;
+use core::hash::{Hash, Hasher};
+use portable_atomic::{AtomicUsize, Ordering};
+
+/// https://html.spec.whatwg.org/multipage/browsers.html#concept-origin
+///
+/// `==` compares origins with same origin.
+#[derive(Clone, Debug)]
+pub enum Origin {
+    Tuple(TupleOrigin),
+    Opaque(OpaqueOrigin),
+}
+
+/// https://html.spec.whatwg.org/multipage/browsers.html#concept-origin-tuple
+#[derive(Clone, Debug)]
+pub struct TupleOrigin {
+    pub scheme: String,
+    pub host: Host,
+    pub port: Option<u16>,
+    /// Set through `document.domain`. It is only compared by `Origin::same_origin_domain`.
+    pub domain: Option<String>,
+}
+
+/// https://html.spec.whatwg.org/multipage/browsers.html#concept-origin-opaque
+///
+/// Every opaque origin is only the same origin as itself and its copies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OpaqueOrigin(usize);
+
+impl OpaqueOrigin {
+    /// A new opaque origin, different from every other one.
+    ///
+    /// The ids come from a `portable-atomic` counter, so this also builds on targets without atomic
+    /// read-modify-write operations once one of its fallbacks is enabled.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        OpaqueOrigin(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Origin {
+    pub fn is_opaque(&self) -> bool {
+        matches!(self, Origin::Opaque(_))
+    }
+
+    /// https://html.spec.whatwg.org/multipage/browsers.html#same-origin
+    pub fn same_origin(&self, other: &Origin) -> bool {
+        match (self, other) {
+            // If A and B are the same opaque origin, then return true.
+            (Origin::Opaque(a), Origin::Opaque(b)) => a == b,
+            // If A and B are both tuple origins and their schemes, hosts, and port are identical, then return true.
+            (Origin::Tuple(a), Origin::Tuple(b)) => {
+                a.scheme == b.scheme && a.host == b.host && a.port == b.port
+            }
+            // Return false.
+            _ => false,
+        }
+    }
+
+    /// https://html.spec.whatwg.org/multipage/browsers.html#same-origin-domain
+    pub fn same_origin_domain(&self, other: &Origin) -> bool {
+        match (self, other) {
+            // If A and B are the same opaque origin, then return true.
+            (Origin::Opaque(a), Origin::Opaque(b)) => a == b,
+            // If A and B are both tuple origins, run these substeps:
+            (Origin::Tuple(a), Origin::Tuple(b)) => {
+                // If A and B's schemes are identical, and their domains are identical and non-null, then return true.
+                if a.domain.is_some() && a.scheme == b.scheme && a.domain == b.domain {
+                    return true;
+                }
+
+                // Otherwise, if A and B are same origin and their domains are both null, return true.
+                self.same_origin(other) && a.domain.is_none() && b.domain.is_none()
+            }
+            // Return false.
+            _ => false,
+        }
+    }
+
+    /// https://w3c.github.io/webappsec-secure-contexts/#is-origin-trustworthy
+    pub fn is_potentially_trustworthy(&self) -> bool {
+        TrustworthyOrigins::default().is_origin_potentially_trustworthy(self)
+    }
+}
+
+impl Origin {
+    /// https://html.spec.whatwg.org/multipage/browsers.html#concept-site-same-site
+    ///
+    /// Registrable domains are looked up in the given list.
+    pub fn same_site(&self, other: &Origin, list: &PublicSuffixList) -> bool {
+        match (self, other) {
+            // If A and B are both tuple origins, and A's scheme does not equal B's scheme, then return false.
+            (Origin::Tuple(a), Origin::Tuple(b)) if a.scheme != b.scheme => false,
+            // If A and B are the same opaque origin, then return true.
+            // Otherwise, return whether A and B are schemelessly same site.
+            _ => self.schemelessly_same_site(other, list),
+        }
+    }
+
+    /// https://html.spec.whatwg.org/multipage/browsers.html#schemelessly-same-site
+    ///
+    /// Registrable domains are looked up in the given list.
+    pub fn schemelessly_same_site(&self, other: &Origin, list: &PublicSuffixList) -> bool {
+        match (self, other) {
+            // If A and B are the same opaque origin, then return true.
+            (Origin::Opaque(a), Origin::Opaque(b)) => a == b,
+            // If A and B are both tuple origins, then:
+            (Origin::Tuple(a), Origin::Tuple(b)) => {
+                // Let hostA be A's host, and let hostB be B's host.
+                match (
+                    registrable_domain(&a.host, list),
+                    registrable_domain(&b.host, list),
+                ) {
+                    // If hostA equals hostB and hostA's registrable domain is null, then return true.
+                    (None, _) => a.host == b.host,
+                    // If hostA's registrable domain equals hostB's registrable domain and is non-null, then return true.
+                    (Some(a), b) => Some(a) == b,
+                }
+            }
+            // Return false.
+            _ => false,
+        }
+    }
+}
+
+/// https://url.spec.whatwg.org/#host-registrable-domain
+fn registrable_domain<'a>(host: &'a Host, list: &PublicSuffixList) -> Option<&'a str> {
+    match host {
+        Host::Domain(domain) => list.registrable_domain(domain),
+        _ => None,
+    }
+}
+
+/// https://html.spec.whatwg.org/multipage/browsers.html#ascii-serialisation-of-an-origin
+impl core::fmt::Display for Origin {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            // If origin is an opaque origin, then return "null".
+            Origin::Opaque(_) => f.write_str("null"),
+            // Otherwise, let result be origin's scheme, followed by "://", followed by origin's host, serialized.
+            // If origin's port is non-null, append a U+003A COLON character (:), and origin's port, serialized, to result.
+            Origin::Tuple(origin) => {
+                write!(f, "{}://{}", origin.scheme, origin.host)?;
+
+                if let Some(port) = origin.port {
+                    write!(f, ":{}", port)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl PartialEq for Origin {
+    fn eq(&self, other: &Origin) -> bool {
+        self.same_origin(other)
+    }
+}
+
+impl Eq for Origin {}
+
+impl Hash for Origin {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Origin::Opaque(origin) => origin.hash(state),
+            Origin::Tuple(origin) => {
+                origin.scheme.hash(state);
+                origin.host.hash(state);
+                origin.port.hash(state);
+            }
+        }
+    }
+}
@@ -1,9 +1,16 @@
-use crate::serializers::{serialize_ipv4, serialize_ipv6};
-use idna::domain_to_ascii;
+use crate::host;
+use crate::machine::ParseOptions;
+use crate::uts46::Uts46Options;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::net::{Ipv4Addr, Ipv6Addr};
 use percent_encoding::{utf8_percent_encode, CONTROLS};
 
 /// https://url.spec.whatwg.org/#ipv4-number-parser
-fn parse_ipv4_number(buffer: String) -> Option<u32> {
+///
+/// Numbers too large for a `u64` are returned as `u64::MAX`, which is too large for any IPv4 address.
+fn parse_ipv4_number(buffer: &str) -> Option<u64> {
     let mut buffer = buffer;
 
     // If input is the empty string, then return failure.
@@ -17,15 +24,15 @@ fn parse_ipv4_number(buffer: String) -> Option<u32> {
     // If input contains at least two code points and the first two code points are either "0X" or "0x", then:
     if buffer.len() >= 2 && (buffer.starts_with("0X") || buffer.starts_with("0x")) {
         // Remove the first two code points from input.
-        buffer = buffer[2..].to_string();
+        buffer = &buffer[2..];
 
         // Set R to 16.
         r = 16;
     }
     // Otherwise, if input contains at least two code points and the first code point is U+0030 (0), then:
-    else if buffer.len() > 2 && buffer.starts_with("0") {
+    else if buffer.len() >= 2 && buffer.starts_with('0') {
         // Remove the first code point from input.
-        buffer = buffer[1..].to_string();
+        buffer = &buffer[1..];
 
         // Set R to 8.
         r = 8;
@@ -37,17 +44,16 @@ fn parse_ipv4_number(buffer: String) -> Option<u32> {
     }
 
     // If input contains a code point that is not a radix-R digit, then return failure.
-    let radix = u32::from_str_radix(buffer.as_str(), r);
-
-    // Let output be the mathematical integer value that is represented by input in radix-R notation, using ASCII hex digits for digits with values 0 through 15.
-    if let Ok(value) = radix {
-        return Some(value);
+    if !buffer.chars().all(|c| c.is_digit(r)) {
+        return None;
     }
 
-    None
+    // Let output be the mathematical integer value that is represented by input in radix-R notation, using ASCII hex digits for digits with values 0 through 15.
+    Some(u64::from_str_radix(buffer, r).unwrap_or(u64::MAX))
 }
 
-pub fn parse_ipv4(buffer: String) -> Option<u64> {
+/// https://url.spec.whatwg.org/#concept-ipv4-parser
+pub fn parse_ipv4(buffer: String) -> Option<Ipv4Addr> {
     // Let parts be the result of strictly splitting input on U+002E (.).
     let mut parts: Vec<&str> = buffer.split('.').collect();
 
@@ -70,41 +76,38 @@ pub fn parse_ipv4(buffer: String) -> Option<u64> {
     // For each part of parts:
     for part in &parts {
         // Let result be the result of parsing part.
-        let result = parse_ipv4_number(part.to_string());
+        // If result is failure, validation error, return failure.
+        let number = parse_ipv4_number(part)?;
 
-        if let Some(number) = result {
-            // If any item in numbers is greater than 255, validation error.
-            if number > 255 {
-                return None;
-            }
+        // Append result[0] to numbers.
+        numbers.push(number)
+    }
 
-            // Append result[0] to numbers.
-            numbers.push(number as u64)
-        } else {
-            // If result is failure, validation error, return failure.
-            return None;
-        }
+    // Let ipv4 be the last item in numbers.
+    // Remove the last item from numbers.
+    let mut ipv4: u64 = numbers.pop()?;
+
+    // If any item in numbers is greater than 255, then return failure.
+    if numbers.iter().any(|n| *n > 255) {
+        return None;
     }
 
     // If the last item in numbers is greater than or equal to 256(5 − numbers’s size), validation error, return failure.
-    if numbers.last() > Some(&256_u64.pow(5 - numbers.len() as u32)) {
+    if ipv4 >= 256_u64.pow(4 - numbers.len() as u32) {
         return None;
     }
 
-    // Let ipv4 be the last item in numbers.
-    // Remove the last item from numbers.
-    let mut ipv4: u64 = numbers.pop().unwrap() as u64;
-
     // For each n of numbers:
     for (c, n) in numbers.iter().enumerate() {
         // Increment ipv4 by n × 256(3 − counter).
-        ipv4 += (n.clone() as u64) * 256_u64.pow(3 - c as u32);
+        ipv4 += n * 256_u64.pow(3 - c as u32);
     }
 
-    Some(ipv4)
+    Some(Ipv4Addr::from(ipv4 as u32))
 }
 
-pub fn parse_ipv6(buffer: String) -> Option<String> {
+/// https://url.spec.whatwg.org/#concept-ipv6-parser
+pub fn parse_ipv6(buffer: String) -> Option<Ipv6Addr> {
     // Let address be a new IPv6 address whose IPv6 pieces are all 0.
     let mut address: Vec<u32> = vec![0; 8];
 
@@ -309,10 +312,12 @@ pub fn parse_ipv6(buffer: String) -> Option<String> {
         return None;
     }
 
-    Some(serialize_ipv6(address))
+    Some(Ipv6Addr::from(core::array::from_fn::<u16, 8, _>(|index| {
+        address[index] as u16
+    })))
 }
 
-pub fn parse_opaque_host(buffer: String) -> String {
+pub fn parse_opaque_host(buffer: &str) -> Option<String> {
     // If input contains a forbidden host code point, validation error, return failure.
     let is_invalid_host_char = |c| {
         matches!(
@@ -337,10 +342,10 @@ pub fn parse_opaque_host(buffer: String) -> String {
     };
 
     if buffer.find(is_invalid_host_char).is_some() {
-        return "".to_string();
+        return None;
     }
 
-    utf8_percent_encode(buffer.as_str(), CONTROLS).to_string()
+    Some(utf8_percent_encode(buffer, CONTROLS).to_string())
 }
 
 pub fn ends_with_a_number(domain: &str) -> bool {
@@ -369,73 +374,18 @@ pub fn ends_with_a_number(domain: &str) -> bool {
     }
 
     // If parsing last as an IPv4 number does not return failure, then return true.
-    if let Some(_number) = parse_ipv4_number(domain.to_string()) {
-        return true;
-    }
-
-    false
+    // Return false.
+    last.is_some_and(|last_item| parse_ipv4_number(last_item).is_some())
 }
 
 /// Returns empty string if fails
-pub fn parse_host(buffer: String, is_not_url_special: bool) -> String {
-    // If input starts with U+005B ([), then:
-    if buffer.starts_with("[") {
-        // If input does not end with U+005D (]), validation error, return failure.
-        if !buffer.ends_with("]") {
-            return "".to_string();
-        }
-
-        // Return the result of IPv6 parsing input with its leading U+005B ([) and trailing U+005D (]) removed.
-        return parse_ipv6(buffer[1..buffer.len() - 1].to_string()).unwrap_or("".to_string());
-    }
-
-    // If isNotSpecial is true, then return the result of opaque-host parsing input.
-    if is_not_url_special {
-        return parse_opaque_host(buffer);
-    }
-
-    let ascii_domain = domain_to_ascii(buffer.as_str()).unwrap_or("".to_string());
-
-    // If asciiDomain is failure, validation error, return failure.
-    if ascii_domain.is_empty() {
-        return "".to_string();
-    }
-
-    // If asciiDomain contains a forbidden domain code point, validation error, return failure.
-    let is_invalid_domain_char = |c| {
-        matches!(
-            c,
-            '\0'..='\u{001F}'
-                | ' '
-                | '#'
-                | '%'
-                | '/'
-                | ':'
-                | '<'
-                | '>'
-                | '?'
-                | '@'
-                | '['
-                | '\\'
-                | ']'
-                | '^'
-                | '\u{007F}'
-                | '|'
-        )
-    };
-
-    if ascii_domain.find(is_invalid_domain_char).is_some() {
-        return "".to_string();
-    }
-
-    // If asciiDomain ends in a number, then return the result of IPv4 parsing asciiDomain.
-    if ends_with_a_number(ascii_domain.as_str()) {
-        return if let Some(result) = parse_ipv4(ascii_domain) {
-            serialize_ipv4(result)
-        } else {
-            "".to_string()
-        };
-    }
-
-    ascii_domain.to_string()
+pub fn parse_host(buffer: String, is_not_url_special: bool, options: &ParseOptions) -> String {
+    host::parse_host_with_zone_id(
+        &buffer,
+        is_not_url_special,
+        &Uts46Options::default(),
+        options.ipv6_zone_id,
+    )
+    .map(|host| host.to_string())
+    .unwrap_or_default()
 }
@@ -1,4 +1,4 @@
-use std::borrow::Borrow;
+use core::borrow::Borrow;
 
 const FILE_CODE_POINTS: [char; 4] = ['/', '\\', '?', '#'];
 
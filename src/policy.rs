@@ -0,0 +1,195 @@
+use crate::allowlist::HostAllowlist;
+use crate::domain::SpecialUseDomain;
+use crate::host::Host;
+use crate::ip::{special_purpose, SpecialPurpose};
+use crate::machine::URLStateMachine;
+use crate::url::URL;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::net::IpAddr;
+
+/// The rule of a `UrlPolicy` that rejected a URL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyViolation {
+    /// The serialized URL is longer than `max_length`.
+    TooLong(usize),
+    /// The scheme is not in `allowed_schemes`.
+    Scheme(String),
+    /// The URL has a username or password.
+    Credentials,
+    /// The URL has no host, an empty host, or an opaque host.
+    NoHost,
+    /// The port is not in `allowed_ports`, or is in `blocked_ports`.
+    Port(u16),
+    /// `allowed_ports` is set, but the URL has no port and its scheme has no default port.
+    NoPort,
+    /// The host is an IP address in a special-purpose block missing from `allowed_ip_purposes`.
+    SpecialPurposeIp(SpecialPurpose),
+    /// The host is a special-use domain in `blocked_special_use_domains`.
+    SpecialUseDomain(SpecialUseDomain),
+    /// The host is a domain of a single label, like "intranet".
+    SingleLabelDomain,
+    /// The host does not match `allowed_hosts`.
+    HostNotAllowed,
+    /// A redirect target that is not a valid URL.
+    InvalidRedirect,
+    /// More than `max_redirects` redirects were followed.
+    TooManyRedirects,
+}
+
+/// Validates URLs before they are fetched, to defend against server-side request forgery.
+///
+/// Hosts are checked as the URL parser canonicalized them, so "0x7f.1" and "[::ffff:127.0.0.1]" are
+/// both loopback addresses. Domains can still resolve to any address: pass the addresses a connection
+/// is made to through `check_ip` as well.
+#[derive(Clone, Debug)]
+pub struct UrlPolicy {
+    pub allowed_schemes: Vec<String>,
+    /// `None` allows every port. The default port of the scheme is used if the URL has none, and
+    /// URLs without either are rejected when this is set.
+    pub allowed_ports: Option<Vec<u16>>,
+    pub blocked_ports: Vec<u16>,
+    /// Special-purpose IP blocks hosts may be in. IP addresses outside every block are always allowed.
+    pub allowed_ip_purposes: Vec<SpecialPurpose>,
+    /// Special-use domains hosts may not be, or be a subdomain of.
+    pub blocked_special_use_domains: Vec<SpecialUseDomain>,
+    pub allow_single_label_domains: bool,
+    pub allow_credentials: bool,
+    /// If set, hosts must also match this list.
+    pub allowed_hosts: Option<HostAllowlist>,
+    pub max_length: Option<usize>,
+    pub max_redirects: usize,
+}
+
+impl Default for UrlPolicy {
+    /// Only "http" and "https" URLs without credentials, to hosts that are not on a local network.
+    fn default() -> Self {
+        UrlPolicy {
+            allowed_schemes: vec!["http".to_string(), "https".to_string()],
+            allowed_ports: None,
+            blocked_ports: vec![],
+            allowed_ip_purposes: vec![],
+            blocked_special_use_domains: vec![
+                SpecialUseDomain::Localhost,
+                SpecialUseDomain::Local,
+                SpecialUseDomain::HomeArpa,
+                SpecialUseDomain::Internal,
+            ],
+            allow_single_label_domains: false,
+            allow_credentials: false,
+            allowed_hosts: None,
+            max_length: Some(8192),
+            max_redirects: 10,
+        }
+    }
+}
+
+impl UrlPolicy {
+    /// Returns the first rule the URL breaks, checking the scheme, credentials, host and port in that order.
+    pub fn check(&self, url: &URL) -> Result<(), PolicyViolation> {
+        if let Some(max_length) = self.max_length {
+            let length = url.to_string().len();
+
+            if length > max_length {
+                return Err(PolicyViolation::TooLong(length));
+            }
+        }
+
+        if !self.allowed_schemes.contains(&url.scheme) {
+            return Err(PolicyViolation::Scheme(url.scheme.clone()));
+        }
+
+        if !self.allow_credentials && (!url.username.is_empty() || !url.password.is_empty()) {
+            return Err(PolicyViolation::Credentials);
+        }
+
+        let host = match url.parsed_host() {
+            Some(Host::Opaque(_)) | None => return Err(PolicyViolation::NoHost),
+            Some(host) => host,
+        };
+
+        match &host {
+            Host::Domain(domain) => {
+                if let Some(special_use) = host.special_use_domain() {
+                    if self.blocked_special_use_domains.contains(&special_use) {
+                        return Err(PolicyViolation::SpecialUseDomain(special_use));
+                    }
+                }
+
+                let domain = domain.strip_suffix('.').unwrap_or(domain);
+
+                if !self.allow_single_label_domains && !domain.contains('.') {
+                    return Err(PolicyViolation::SingleLabelDomain);
+                }
+            }
+            host => {
+                if let Some(address) = host.ip() {
+                    self.check_ip(address)?;
+                }
+            }
+        }
+
+        if let Some(allowed_hosts) = &self.allowed_hosts {
+            if !allowed_hosts.matches(&host) {
+                return Err(PolicyViolation::HostNotAllowed);
+            }
+        }
+
+        let port = match &url.port {
+            Some(port) => port.parse::<u16>().ok(),
+            None => url.scheme_type.default_port(),
+        };
+
+        match port {
+            Some(port) => {
+                let is_allowed = match &self.allowed_ports {
+                    Some(allowed_ports) => allowed_ports.contains(&port),
+                    None => true,
+                };
+
+                if !is_allowed || self.blocked_ports.contains(&port) {
+                    return Err(PolicyViolation::Port(port));
+                }
+            }
+            // A URL without a port can only be allowed if every port is.
+            None if self.allowed_ports.is_some() => return Err(PolicyViolation::NoPort),
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    /// Checks an address a domain resolved to, before connecting to it.
+    pub fn check_ip(&self, address: IpAddr) -> Result<(), PolicyViolation> {
+        match special_purpose(address) {
+            Some(purpose) if !self.allowed_ip_purposes.contains(&purpose) => {
+                Err(PolicyViolation::SpecialPurposeIp(purpose))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolves the Location of a redirect response against the URL that was fetched, and checks the result.
+    /// `redirects` is the number of redirects followed before this one.
+    pub fn follow_redirect(
+        &self,
+        from: &URL,
+        location: &str,
+        redirects: usize,
+    ) -> Result<URL, PolicyViolation> {
+        if redirects >= self.max_redirects {
+            return Err(PolicyViolation::TooManyRedirects);
+        }
+
+        let machine = URLStateMachine::new(location, Some(from), None, None);
+
+        if machine.failure {
+            return Err(PolicyViolation::InvalidRedirect);
+        }
+
+        self.check(&machine.url)?;
+
+        Ok(machine.url)
+    }
+}
@@ -0,0 +1,154 @@
+use crate::uts46::{domain_to_ascii, Uts46Options};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+
+/// A snapshot of https://publicsuffix.org/list/public_suffix_list.dat, for `PublicSuffixList::parse`
+/// where `PublicSuffixList::bundled` is not available.
+pub const BUNDLED_LIST: &str = include_str!("../data/public_suffix_list.dat");
+
+/// The section of the list a rule comes from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Section {
+    /// Suffixes delegated by the registries of ICANN top-level domains.
+    Icann,
+    /// Suffixes that private parties, such as hosting providers, have asked to be listed.
+    Private,
+}
+
+/// The public suffix of a domain, and the section of the rule that produced it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Suffix<'a> {
+    pub suffix: &'a str,
+    /// `None` if no rule matched and the implicit "*" rule was used.
+    pub section: Option<Section>,
+}
+
+/// https://github.com/publicsuffix/list/wiki/Format
+///
+/// Rules are stored in their ASCII form, so lookups expect hosts as `parse_host` returns them.
+#[derive(Clone, Debug, Default)]
+pub struct PublicSuffixList {
+    /// Keyed by the rule as written, with wildcard rules starting with "*." and exception rules with "!".
+    rules: BTreeMap<String, Section>,
+}
+
+impl PublicSuffixList {
+    /// Parses a list in the format of `public_suffix_list.dat`. Rules that are not valid domains are skipped.
+    pub fn parse(list: &str) -> Self {
+        let mut rules = BTreeMap::new();
+        let mut section = Section::Icann;
+
+        for line in list.lines() {
+            let line = line.trim();
+
+            if let Some(comment) = line.strip_prefix("//") {
+                match comment.trim() {
+                    "===BEGIN ICANN DOMAINS===" => section = Section::Icann,
+                    "===BEGIN PRIVATE DOMAINS===" => section = Section::Private,
+                    _ => {}
+                }
+
+                continue;
+            }
+
+            // Each line is only read up to the first whitespace.
+            let line = line.split_whitespace().next().unwrap_or("");
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (prefix, domain) = if let Some(domain) = line.strip_prefix('!') {
+                ("!", domain)
+            } else if let Some(domain) = line.strip_prefix("*.") {
+                ("*.", domain)
+            } else {
+                ("", line)
+            };
+
+            if let Ok(domain) = domain_to_ascii(domain, &Uts46Options::default()) {
+                rules.insert(prefix.to_string() + domain.as_str(), section);
+            }
+        }
+
+        PublicSuffixList { rules }
+    }
+
+    /// The list bundled with this crate, parsed on first use.
+    #[cfg(feature = "std")]
+    pub fn bundled() -> &'static PublicSuffixList {
+        static LIST: std::sync::OnceLock<PublicSuffixList> = std::sync::OnceLock::new();
+
+        LIST.get_or_init(|| PublicSuffixList::parse(BUNDLED_LIST))
+    }
+
+    /// https://github.com/publicsuffix/list/wiki/Format#formal-algorithm
+    ///
+    /// A trailing dot on the domain is kept on the suffix. Rules of the private section are only
+    /// considered if `include_private` is true.
+    pub fn lookup<'a>(&self, domain: &'a str, include_private: bool) -> Option<Suffix<'a>> {
+        let name = domain.strip_suffix('.').unwrap_or(domain);
+
+        if name.is_empty() || name.split('.').any(str::is_empty) {
+            return None;
+        }
+
+        let rule = |key: String| {
+            self.rules
+                .get(&key)
+                .copied()
+                .filter(|section| include_private || *section == Section::Icann)
+        };
+
+        // The start of each label, from the leftmost one, so the first matching rule is the longest.
+        let starts = core::iter::once(0).chain(name.match_indices('.').map(|(index, _)| index + 1));
+
+        for start in starts {
+            let candidate = &name[start..];
+            let parent = candidate.split_once('.').map(|(_, parent)| parent);
+
+            // If the prevailing rule is an exception rule, the public suffix is the rule without its leftmost label.
+            if let Some(section) = rule("!".to_string() + candidate) {
+                let suffix = parent.map(|parent| &domain[name.len() - parent.len()..]);
+
+                return suffix.map(|suffix| Suffix {
+                    suffix,
+                    section: Some(section),
+                });
+            }
+
+            let section = rule(candidate.to_string())
+                .or_else(|| parent.and_then(|parent| rule("*.".to_string() + parent)));
+
+            if section.is_some() {
+                return Some(Suffix {
+                    suffix: &domain[start..],
+                    section,
+                });
+            }
+        }
+
+        // If no rules match, the prevailing rule is "*".
+        let start = name.rfind('.').map_or(0, |index| index + 1);
+
+        Some(Suffix {
+            suffix: &domain[start..],
+            section: None,
+        })
+    }
+
+    /// The public suffix of the domain, with rules of both sections.
+    pub fn public_suffix<'a>(&self, domain: &'a str) -> Option<&'a str> {
+        self.lookup(domain, true).map(|suffix| suffix.suffix)
+    }
+
+    /// The public suffix of the domain plus one more label, with rules of both sections.
+    /// Returns `None` if the domain is itself a public suffix.
+    pub fn registrable_domain<'a>(&self, domain: &'a str) -> Option<&'a str> {
+        let suffix = self.public_suffix(domain)?;
+        let rest = domain[..domain.len() - suffix.len()].strip_suffix('.')?;
+        let start = rest.rfind('.').map_or(0, |index| index + 1);
+
+        Some(&domain[start..])
+    }
+}
@@ -0,0 +1,90 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use percent_encoding::percent_decode_str;
+
+/// Query parameters that commonly carry credentials or signatures.
+pub const SENSITIVE_QUERY_PARAMS: &[&str] = &[
+    "access_token",
+    "api_key",
+    "apikey",
+    "auth",
+    "client_secret",
+    "code",
+    "id_token",
+    "key",
+    "password",
+    "refresh_token",
+    "secret",
+    "sig",
+    "signature",
+    "token",
+    "X-Amz-Credential",
+    "X-Amz-Security-Token",
+    "X-Amz-Signature",
+    "X-Goog-Credential",
+    "X-Goog-Signature",
+];
+
+/// What to do with the username and password of a URL.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Userinfo {
+    /// Keep them as they are.
+    Keep,
+    /// Remove them, along with the U+0040 (@) that ends them.
+    Strip,
+    /// Replace each non-empty one with the mask.
+    #[default]
+    Mask,
+}
+
+/// How `URL::redacted` renders a URL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedactOptions {
+    pub userinfo: Userinfo,
+    /// Names of the query parameters whose values are replaced with the mask, compared
+    /// ignoring ASCII case after percent-decoding.
+    pub sensitive_query_params: Vec<String>,
+    /// What redacted values are replaced with. It is written as it is, so it should not need percent-encoding.
+    pub mask: String,
+}
+
+impl Default for RedactOptions {
+    /// Masks userinfo and the values of `SENSITIVE_QUERY_PARAMS`.
+    fn default() -> Self {
+        RedactOptions {
+            userinfo: Userinfo::Mask,
+            sensitive_query_params: SENSITIVE_QUERY_PARAMS
+                .iter()
+                .map(|name| String::from(*name))
+                .collect(),
+            mask: String::from("REDACTED"),
+        }
+    }
+}
+
+impl RedactOptions {
+    fn is_sensitive(&self, name: &str) -> bool {
+        // Names are decoded the same way as application/x-www-form-urlencoded parsing does.
+        let name = name.replace('+', " ");
+        let name = percent_decode_str(&name).decode_utf8_lossy();
+
+        self.sensitive_query_params
+            .iter()
+            .any(|sensitive| sensitive.eq_ignore_ascii_case(&name))
+    }
+
+    /// The query with the values of sensitive parameters replaced with the mask.
+    pub(crate) fn redact_query(&self, query: &str) -> String {
+        let pairs: Vec<String> = query
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, value)) if !value.is_empty() && self.is_sensitive(name) => {
+                    String::from(name) + "=" + &self.mask
+                }
+                _ => String::from(pair),
+            })
+            .collect();
+
+        pairs.join("&")
+    }
+}
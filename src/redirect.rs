@@ -0,0 +1,59 @@
+use crate::machine::URLStateMachine;
+use crate::origin::Origin;
+use crate::url::URL;
+use alloc::vec::Vec;
+
+/// Why a redirect target was rejected by `RedirectTargets::check`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnsafeRedirect {
+    /// The target is not a valid URL relative to the current URL.
+    Invalid,
+    /// The target is not the same origin as the current URL or any allowed origin.
+    CrossOrigin,
+    /// The target has a username or password, which can be used to disguise its host.
+    Credentials,
+}
+
+/// Decides whether a user-supplied target, like a `returnTo` parameter, is safe to redirect to.
+///
+/// The target is parsed like a browser would, so backslashes, ASCII tab or newline, "//evil.com",
+/// "/\evil.com" and "https://good.com@evil.com" all end up with the host a browser would go to.
+#[derive(Clone, Debug, Default)]
+pub struct RedirectTargets {
+    /// Origins to allow besides the origin of the current URL.
+    pub allowed_origins: Vec<Origin>,
+}
+
+impl RedirectTargets {
+    pub fn new(allowed_origins: Vec<Origin>) -> Self {
+        RedirectTargets { allowed_origins }
+    }
+
+    /// Parses the target relative to the current URL. Redirect to the serialization of the returned URL,
+    /// never to the target as given, so that every client agrees on where it goes.
+    pub fn check(&self, target: &str, current: &URL) -> Result<URL, UnsafeRedirect> {
+        let machine = URLStateMachine::new(target, Some(current), None, None);
+
+        if machine.failure {
+            return Err(UnsafeRedirect::Invalid);
+        }
+
+        // Opaque origins, like those of "javascript:" and "data:" URLs, are never the same origin as another URL.
+        let origin = machine.url.origin();
+
+        if !origin.same_origin(&current.origin())
+            && !self
+                .allowed_origins
+                .iter()
+                .any(|allowed| origin.same_origin(allowed))
+        {
+            return Err(UnsafeRedirect::CrossOrigin);
+        }
+
+        if !machine.url.username.is_empty() || !machine.url.password.is_empty() {
+            return Err(UnsafeRedirect::Credentials);
+        }
+
+        Ok(machine.url)
+    }
+}
@@ -0,0 +1,183 @@
+use crate::url::URL;
+use alloc::string::ToString;
+use alloc::vec;
+
+/// https://w3c.github.io/webappsec-referrer-policy/#referrer-policies
+///
+/// The empty string policy is represented by `None`, to be replaced with `ReferrerPolicy::default()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ReferrerPolicy {
+    NoReferrer,
+    NoReferrerWhenDowngrade,
+    SameOrigin,
+    Origin,
+    StrictOrigin,
+    OriginWhenCrossOrigin,
+    /// https://fetch.spec.whatwg.org/#default-referrer-policy
+    #[default]
+    StrictOriginWhenCrossOrigin,
+    UnsafeUrl,
+}
+
+impl ReferrerPolicy {
+    /// The policy with the given token, like "strict-origin".
+    pub fn parse(token: &str) -> Option<ReferrerPolicy> {
+        match token {
+            "no-referrer" => Some(ReferrerPolicy::NoReferrer),
+            "no-referrer-when-downgrade" => Some(ReferrerPolicy::NoReferrerWhenDowngrade),
+            "same-origin" => Some(ReferrerPolicy::SameOrigin),
+            "origin" => Some(ReferrerPolicy::Origin),
+            "strict-origin" => Some(ReferrerPolicy::StrictOrigin),
+            "origin-when-cross-origin" => Some(ReferrerPolicy::OriginWhenCrossOrigin),
+            "strict-origin-when-cross-origin" => Some(ReferrerPolicy::StrictOriginWhenCrossOrigin),
+            "unsafe-url" => Some(ReferrerPolicy::UnsafeUrl),
+            _ => None,
+        }
+    }
+
+    /// https://w3c.github.io/webappsec-referrer-policy/#parse-referrer-policy-from-header
+    ///
+    /// Takes the value of the `Referrer-Policy` header. Unknown tokens are skipped, so that new policies can be
+    /// listed after a fallback.
+    pub fn from_header(value: &str) -> Option<ReferrerPolicy> {
+        // Let policy be the empty string.
+        // For each token in policy-tokens, if token is a referrer policy and token is not the empty string,
+        // then set policy to token.
+        // Return policy.
+        value
+            .rsplit(',')
+            .find_map(|token| ReferrerPolicy::parse(token.trim_matches([' ', '\t'])))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferrerPolicy::NoReferrer => "no-referrer",
+            ReferrerPolicy::NoReferrerWhenDowngrade => "no-referrer-when-downgrade",
+            ReferrerPolicy::SameOrigin => "same-origin",
+            ReferrerPolicy::Origin => "origin",
+            ReferrerPolicy::StrictOrigin => "strict-origin",
+            ReferrerPolicy::OriginWhenCrossOrigin => "origin-when-cross-origin",
+            ReferrerPolicy::StrictOriginWhenCrossOrigin => "strict-origin-when-cross-origin",
+            ReferrerPolicy::UnsafeUrl => "unsafe-url",
+        }
+    }
+}
+
+impl core::fmt::Display for ReferrerPolicy {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// https://w3c.github.io/webappsec-referrer-policy/#strip-url
+///
+/// Returns `None` for no referrer.
+pub fn strip_for_referrer(url: &URL, origin_only: bool) -> Option<URL> {
+    // If url’s scheme is a local scheme, then return no referrer.
+    if matches!(url.scheme.as_str(), "about" | "blob" | "data") {
+        return None;
+    }
+
+    let mut url = url.clone();
+
+    // Set url’s username to the empty string.
+    url.username.clear();
+
+    // Set url’s password to the empty string.
+    url.password.clear();
+
+    // Set url’s fragment to null.
+    url.fragment = None;
+
+    // If the origin-only flag is true, then:
+    if origin_only {
+        // Set url’s path to « the empty string ».
+        url.path = vec!["".to_string()];
+        url.has_opaque_path = false;
+
+        // Set url’s query to null.
+        url.query = None;
+    }
+
+    // Return url.
+    Some(url)
+}
+
+/// https://w3c.github.io/webappsec-referrer-policy/#determine-requests-referrer
+///
+/// `referrer_source` is the URL of the document or worker making the request, and `current` is the URL being
+/// requested. Returns `None` for no referrer; the serialization of the result is the value of the `Referer` header.
+pub fn determine_referrer(
+    referrer_source: &URL,
+    current: &URL,
+    policy: ReferrerPolicy,
+) -> Option<URL> {
+    // Let referrerURL be the result of stripping referrerSource for use as a referrer.
+    let referrer_url = strip_for_referrer(referrer_source, false)?;
+
+    // Let referrerOrigin be the result of stripping referrerSource for use as a referrer, with the origin-only flag
+    // set to true.
+    let referrer_origin = strip_for_referrer(referrer_source, true)?;
+
+    // If the result of serializing referrerURL is a string whose length is greater than 4096, set referrerURL to
+    // referrerOrigin.
+    let referrer_url = if referrer_url.to_string().len() > 4096 {
+        referrer_origin.clone()
+    } else {
+        referrer_url
+    };
+
+    let is_same_origin = || referrer_url.origin().same_origin(&current.origin());
+    let is_downgrade =
+        || referrer_url.is_potentially_trustworthy() && !current.is_potentially_trustworthy();
+
+    // Execute the statements corresponding to the value of policy:
+    match policy {
+        // Return no referrer
+        ReferrerPolicy::NoReferrer => None,
+        // Return referrerOrigin
+        ReferrerPolicy::Origin => Some(referrer_origin),
+        // Return referrerURL.
+        ReferrerPolicy::UnsafeUrl => Some(referrer_url),
+        ReferrerPolicy::StrictOrigin => {
+            // If referrerURL is a potentially trustworthy URL and request’s current URL is not a potentially
+            // trustworthy URL, then return no referrer.
+            // Return referrerOrigin.
+            (!is_downgrade()).then_some(referrer_origin)
+        }
+        ReferrerPolicy::StrictOriginWhenCrossOrigin => {
+            // If the origin of referrerURL and the origin of request’s current URL are the same, then return
+            // referrerURL.
+            if is_same_origin() {
+                return Some(referrer_url);
+            }
+
+            // If referrerURL is a potentially trustworthy URL and request’s current URL is not a potentially
+            // trustworthy URL, then return no referrer.
+            // Return referrerOrigin.
+            (!is_downgrade()).then_some(referrer_origin)
+        }
+        ReferrerPolicy::SameOrigin => {
+            // If the origin of referrerURL and the origin of request’s current URL are the same, then return
+            // referrerURL.
+            // Return no referrer.
+            is_same_origin().then_some(referrer_url)
+        }
+        ReferrerPolicy::OriginWhenCrossOrigin => {
+            // If the origin of referrerURL and the origin of request’s current URL are the same, then return
+            // referrerURL.
+            // Return referrerOrigin.
+            if is_same_origin() {
+                Some(referrer_url)
+            } else {
+                Some(referrer_origin)
+            }
+        }
+        ReferrerPolicy::NoReferrerWhenDowngrade => {
+            // If referrerURL is a potentially trustworthy URL and request’s current URL is not a potentially
+            // trustworthy URL, then return no referrer.
+            // Return referrerURL.
+            (!is_downgrade()).then_some(referrer_url)
+        }
+    }
+}
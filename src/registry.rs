@@ -0,0 +1,148 @@
+use crate::machine::URLStateMachine;
+use crate::scheme::SchemeType;
+use crate::url::URL;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+
+/// Default ports of common schemes that are not special, sorted by scheme. Used by `URL::port_or_known_default`
+/// and `SchemeRegistry::with_known_schemes`.
+pub const KNOWN_DEFAULT_PORTS: &[(&str, u16)] = &[
+    ("amqp", 5672),
+    ("amqps", 5671),
+    ("git", 9418),
+    ("gopher", 70),
+    ("imap", 143),
+    ("imaps", 993),
+    ("irc", 6667),
+    ("ircs", 6697),
+    ("ldap", 389),
+    ("ldaps", 636),
+    ("memcached", 11211),
+    ("mongodb", 27017),
+    ("mqtt", 1883),
+    ("mqtts", 8883),
+    ("mysql", 3306),
+    ("nats", 4222),
+    ("nntp", 119),
+    ("pop3", 110),
+    ("pop3s", 995),
+    ("postgres", 5432),
+    ("postgresql", 5432),
+    ("redis", 6379),
+    ("rtsp", 554),
+    ("sftp", 22),
+    ("smtp", 25),
+    ("smtps", 465),
+    ("ssh", 22),
+    ("telnet", 23),
+];
+
+/// The default port of a scheme in `KNOWN_DEFAULT_PORTS`.
+pub fn known_default_port(scheme: &str) -> Option<u16> {
+    KNOWN_DEFAULT_PORTS
+        .binary_search_by_key(&scheme, |(known, _)| known)
+        .ok()
+        .map(|index| KNOWN_DEFAULT_PORTS[index].1)
+}
+
+/// Why `SchemeRegistry::register` refused a scheme.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegisterError {
+    /// The scheme is special, and its default port is fixed by the URL Standard.
+    SpecialScheme,
+    /// The scheme does not start with an ASCII alpha followed by ASCII alphanumerics, "+", "-" or ".".
+    InvalidScheme,
+}
+
+/// Default ports for schemes that are not special, applied on top of the URL parser.
+///
+/// The parser keeps treating these schemes as not special: their hosts stay opaque and their origins opaque.
+/// Only the port is affected, by `normalize` and `port_or_default`.
+#[derive(Clone, Debug, Default)]
+pub struct SchemeRegistry {
+    default_ports: BTreeMap<String, u16>,
+}
+
+impl SchemeRegistry {
+    /// A registry without any schemes.
+    pub fn new() -> Self {
+        SchemeRegistry::default()
+    }
+
+    /// A registry with the schemes of `KNOWN_DEFAULT_PORTS`.
+    pub fn with_known_schemes() -> Self {
+        SchemeRegistry {
+            default_ports: KNOWN_DEFAULT_PORTS
+                .iter()
+                .map(|(scheme, port)| (String::from(*scheme), *port))
+                .collect(),
+        }
+    }
+
+    /// Registers the default port of a scheme, replacing the one it had. Schemes are ASCII case-insensitive.
+    pub fn register(&mut self, scheme: &str, default_port: u16) -> Result<(), RegisterError> {
+        let mut chars = scheme.chars();
+
+        if !chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            || !chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        {
+            return Err(RegisterError::InvalidScheme);
+        }
+
+        let scheme = scheme.to_ascii_lowercase();
+
+        if SchemeType::from(scheme.as_str()).is_special() {
+            return Err(RegisterError::SpecialScheme);
+        }
+
+        self.default_ports.insert(scheme, default_port);
+
+        Ok(())
+    }
+
+    /// The default port of a special scheme, or else of a registered scheme.
+    pub fn default_port(&self, scheme: &str) -> Option<u16> {
+        SchemeType::from(scheme)
+            .default_port()
+            .or_else(|| self.default_ports.get(scheme).copied())
+    }
+
+    /// The port of the URL, or else the default port of its scheme.
+    pub fn port_or_default(&self, url: &URL) -> Option<u16> {
+        match &url.port {
+            Some(port) => port.parse().ok(),
+            None => self.default_port(&url.scheme),
+        }
+    }
+
+    /// Removes the port of the URL if it is the default port of its registered scheme, like the URL parser
+    /// does for special schemes. Returns true if the URL was changed.
+    pub fn normalize(&self, url: &mut URL) -> bool {
+        if url.scheme_type.is_special() {
+            return false;
+        }
+
+        let is_default = url
+            .port
+            .as_ref()
+            .and_then(|port| port.parse::<u16>().ok())
+            .is_some_and(|port| self.default_ports.get(&url.scheme) == Some(&port));
+
+        if is_default {
+            url.port = None;
+        }
+
+        is_default
+    }
+
+    /// Runs the URL parser and then `normalize`.
+    pub fn parse<'a>(&self, input: &str, base: Option<&'a URL>) -> URLStateMachine<'a> {
+        let mut machine = URLStateMachine::new(input, base, None, None);
+
+        if !machine.failure {
+            self.normalize(&mut machine.url);
+        }
+
+        machine
+    }
+}
@@ -0,0 +1,49 @@
+/// Classification of a URL's scheme, computed once while parsing.
+///
+/// A special scheme is a scheme listed in the first column of the special-scheme table:
+/// https://url.spec.whatwg.org/#special-scheme
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum SchemeType {
+    Ftp,
+    File,
+    Http,
+    Https,
+    Ws,
+    Wss,
+    #[default]
+    NotSpecial,
+}
+
+impl SchemeType {
+    pub fn is_special(&self) -> bool {
+        !matches!(self, SchemeType::NotSpecial)
+    }
+
+    pub fn is_file(&self) -> bool {
+        matches!(self, SchemeType::File)
+    }
+
+    /// A default port is a special scheme's optional corresponding port and is in the second column on the same row.
+    pub fn default_port(&self) -> Option<u16> {
+        match self {
+            SchemeType::Ftp => Some(21),
+            SchemeType::Http | SchemeType::Ws => Some(80),
+            SchemeType::Https | SchemeType::Wss => Some(443),
+            SchemeType::File | SchemeType::NotSpecial => None,
+        }
+    }
+}
+
+impl From<&str> for SchemeType {
+    fn from(scheme: &str) -> Self {
+        match scheme {
+            "ftp" => SchemeType::Ftp,
+            "file" => SchemeType::File,
+            "http" => SchemeType::Http,
+            "https" => SchemeType::Https,
+            "ws" => SchemeType::Ws,
+            "wss" => SchemeType::Wss,
+            _ => SchemeType::NotSpecial,
+        }
+    }
+}
@@ -1,47 +1,64 @@
-use std::borrow::Borrow;
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+use core::net::{Ipv4Addr, Ipv6Addr};
 
-pub fn serialize_ipv4(address: u64) -> String {
-    let mut output: String = String::from("");
-    let mut n = address as f64;
+/// https://url.spec.whatwg.org/#concept-ipv4-serializer
+pub fn serialize_ipv4(address: Ipv4Addr) -> String {
+    let mut output = String::new();
 
-    for i in 1..=4 {
-        output = (n % 256.0).to_string() + output.borrow();
+    for (index, octet) in address.octets().iter().enumerate() {
+        // The octets are the values of n % 256, from the last one prepended to the first.
+        output += octet.to_string().as_str();
 
-        if i != 4 {
-            output = ".".to_string() + output.borrow();
+        // Each octet but the last is followed by U+002E (.).
+        if index != 3 {
+            output += ".";
         }
-
-        n = (n / 256.0).floor();
     }
 
     output
 }
 
-pub fn serialize_ipv6(address: Vec<u32>) -> String {
+/// https://url.spec.whatwg.org/#concept-ipv6-serializer
+///
+/// Unlike the `Display` of `Ipv6Addr`, IPv4-mapped addresses are not written in dotted notation.
+pub fn serialize_ipv6(address: Ipv6Addr) -> String {
+    let address = address.segments();
+
+    // Let compress be an index to the first IPv6 piece in the first longest sequences of address’s IPv6 pieces that are 0.
+    // If there is no sequence of address’s IPv6 pieces that are 0 that is longer than 1, then set compress to null.
     let compress = find_longest_zero_sequence(&address);
-    let mut output = "".to_string();
+    let mut output = String::new();
+
+    // Let ignore0 be false.
     let mut ignore_0 = false;
 
-    for piece_index in 0..8 {
-        if ignore_0 && address[piece_index] == 0 {
+    // For each pieceIndex in the range 0 to 7, inclusive:
+    for (piece_index, piece) in address.iter().enumerate() {
+        // If ignore0 is true and address[pieceIndex] is 0, then continue.
+        if ignore_0 && *piece == 0 {
             continue;
-        } else if ignore_0 {
+        }
+        // Otherwise, if ignore0 is true, set ignore0 to false.
+        else if ignore_0 {
             ignore_0 = false;
         }
 
-        if compress == piece_index {
+        // If compress is pieceIndex, then:
+        if compress == Some(piece_index) {
+            // Let separator be "::" if pieceIndex is 0, and U+003A (:) otherwise.
+            // Append separator to output.
             output += if piece_index == 0 { "::" } else { ":" };
+
+            // Set ignore0 to true and continue.
             ignore_0 = true;
             continue;
         }
 
-        output += char::from_u32(address[piece_index])
-            .unwrap()
-            .to_digit(16)
-            .unwrap_or(0)
-            .to_string()
-            .as_str();
+        // Append address[pieceIndex], represented as the shortest possible lowercase hexadecimal number, to output.
+        let _ = write!(output, "{:x}", piece);
 
+        // If pieceIndex is not 7, then append U+003A (:) to output.
         if piece_index != 7 {
             output += ":";
         }
@@ -50,33 +67,30 @@ pub fn serialize_ipv6(address: Vec<u32>) -> String {
     output
 }
 
-fn find_longest_zero_sequence(address: &Vec<u32>) -> usize {
+fn find_longest_zero_sequence(address: &[u16; 8]) -> Option<usize> {
     let mut max_idx: Option<usize> = None;
     let mut max_length = 1;
     let mut current_start: Option<usize> = None;
     let mut current_length = 0;
 
-    for (index, character) in address.iter().enumerate() {
-        if character != &0 {
-            if current_length > max_length {
-                max_idx = current_start;
-                max_length = current_length;
-            }
-
+    for (index, piece) in address.iter().enumerate() {
+        if *piece != 0 {
             current_start = None;
             current_length = 0;
-        } else {
-            if current_start.is_none() {
-                current_start = Some(index);
-            }
+            continue;
+        }
 
-            current_length += 1;
+        if current_start.is_none() {
+            current_start = Some(index);
         }
-    }
 
-    if current_length > max_length {
-        return current_start.unwrap_or(0);
+        current_length += 1;
+
+        if current_length > max_length {
+            max_idx = current_start;
+            max_length = current_length;
+        }
     }
 
-    max_idx.unwrap_or(0)
+    max_idx
 }
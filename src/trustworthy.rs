@@ -0,0 +1,95 @@
+use crate::host::Host;
+use crate::origin::Origin;
+use crate::url::URL;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::net::Ipv6Addr;
+
+/// Origins and schemes that are potentially trustworthy in addition to those of the Secure Contexts spec,
+/// like the origins of development servers.
+#[derive(Clone, Debug, Default)]
+pub struct TrustworthyOrigins {
+    /// Schemes considered to be authenticated, whose origins are always potentially trustworthy.
+    pub schemes: Vec<String>,
+    /// Origins configured as trustworthy.
+    pub origins: Vec<Origin>,
+}
+
+impl TrustworthyOrigins {
+    pub fn new(origins: Vec<Origin>) -> Self {
+        TrustworthyOrigins {
+            schemes: Vec::new(),
+            origins,
+        }
+    }
+
+    /// https://w3c.github.io/webappsec-secure-contexts/#is-origin-trustworthy
+    pub fn is_origin_potentially_trustworthy(&self, origin: &Origin) -> bool {
+        // If origin is an opaque origin, return "Not Trustworthy".
+        // Assert: origin is a tuple origin.
+        let Origin::Tuple(tuple) = origin else {
+            return false;
+        };
+
+        // If origin’s scheme is either "https" or "wss", return "Potentially Trustworthy".
+        if tuple.scheme == "https" || tuple.scheme == "wss" {
+            return true;
+        }
+
+        // If origin’s host matches one of the CIDR notations 127.0.0.0/8 or ::1/128, return "Potentially Trustworthy".
+        // If the user agent conforms to the name resolution rules in [let-localhost-be-localhost] and one of the
+        // following is true: origin’s host is "localhost" or "localhost.", origin’s host ends with ".localhost" or
+        // ".localhost.", then return "Potentially Trustworthy".
+        if is_localhost(&tuple.host) {
+            return true;
+        }
+
+        // If origin’s scheme is "file", return "Potentially Trustworthy".
+        // If origin’s scheme component is one which the user agent considers to be authenticated, return
+        // "Potentially Trustworthy".
+        if tuple.scheme == "file" || self.schemes.contains(&tuple.scheme) {
+            return true;
+        }
+
+        // If origin has been configured as a trustworthy origin, return "Potentially Trustworthy".
+        // Return "Not Trustworthy".
+        self.origins
+            .iter()
+            .any(|trusted| trusted.same_origin(origin))
+    }
+
+    /// https://w3c.github.io/webappsec-secure-contexts/#is-url-trustworthy
+    ///
+    /// `URL::origin` gives "file" URLs a new opaque origin, so they are recognized by their scheme instead,
+    /// as browsers do.
+    pub fn is_url_potentially_trustworthy(&self, url: &URL) -> bool {
+        // If url is "about:blank" or "about:srcdoc", return "Potentially Trustworthy".
+        if url.scheme == "about"
+            && url.host.is_none()
+            && matches!(url.pathname().as_str(), "blank" | "srcdoc")
+        {
+            return true;
+        }
+
+        // If url’s scheme is "data", return "Potentially Trustworthy".
+        if url.scheme == "data" || url.scheme_type.is_file() {
+            return true;
+        }
+
+        // Return the result of executing § 3.1 Is origin potentially trustworthy? on url’s origin.
+        self.is_origin_potentially_trustworthy(&url.origin())
+    }
+}
+
+fn is_localhost(host: &Host) -> bool {
+    match host {
+        Host::Ipv4(address) => address.is_loopback(),
+        Host::Ipv6(address) | Host::Ipv6WithZone(address, _) => *address == Ipv6Addr::LOCALHOST,
+        Host::Domain(domain) => {
+            let domain = domain.strip_suffix('.').unwrap_or(domain);
+
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        Host::Opaque(_) => false,
+    }
+}
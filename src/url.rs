@@ -1,11 +1,31 @@
+use crate::host::{parse_ipv6_with_zone_id, Host};
+use crate::machine::URLStateMachine;
+use crate::net::{Resolver, SocketAddrsError};
+use crate::origin::{OpaqueOrigin, Origin, TupleOrigin};
+use crate::parser::{ends_with_a_number, parse_ipv4};
+#[cfg(feature = "std")]
+use crate::psl::PublicSuffixList;
+use crate::redact::{RedactOptions, Userinfo};
+use crate::registry::{known_default_port, SchemeRegistry};
+use crate::scheme::SchemeType;
+use crate::trustworthy::TrustworthyOrigins;
+use crate::uts46::{domain_to_unicode, DisplayPolicy, SafeDisplayPolicy};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::net::{IpAddr, SocketAddr, SocketAddrV6};
+
 #[derive(Clone, Debug)]
 pub struct URL {
     pub scheme: String,
+    pub scheme_type: SchemeType,
     pub username: String,
     pub password: String,
     pub host: Option<String>,
     pub port: Option<String>,
     pub path: Vec<String>,
+    /// If true, the path is a single opaque string, as in "mailto:user@example.com", kept in `path[0]`.
+    pub has_opaque_path: bool,
     pub query: Option<String>,
     pub fragment: Option<String>,
 }
@@ -20,13 +40,306 @@ impl URL {
     pub fn new() -> Self {
         URL {
             scheme: "".to_string(),
+            scheme_type: SchemeType::NotSpecial,
             username: "".to_string(),
             password: "".to_string(),
             host: None,
             port: None,
             path: vec![],
+            has_opaque_path: false,
             query: None,
             fragment: None,
         }
     }
+
+    /// The host with its A-labels converted back to Unicode for display, keeping Punycode for
+    /// labels that `SafeDisplayPolicy` deems unsafe. Never use the result to make requests.
+    pub fn host_unicode(&self) -> Option<String> {
+        self.host_unicode_with(&SafeDisplayPolicy)
+    }
+
+    /// Like `host_unicode`, with the given policy deciding which labels are shown in Unicode.
+    pub fn host_unicode_with(&self, policy: &impl DisplayPolicy) -> Option<String> {
+        let host = self.host.as_ref()?;
+
+        // Opaque hosts and IPv6 addresses are not domains.
+        if !self.scheme_type.is_special() || host.starts_with('[') {
+            return Some(host.clone());
+        }
+
+        Some(domain_to_unicode(host, policy))
+    }
+
+    /// The serialization of the URL with its credentials hidden, for logs and error messages.
+    /// The result is still a valid URL.
+    pub fn redacted(&self, options: &RedactOptions) -> String {
+        let mut url = self.clone();
+
+        match options.userinfo {
+            Userinfo::Keep => {}
+            Userinfo::Strip => {
+                url.username.clear();
+                url.password.clear();
+            }
+            Userinfo::Mask => {
+                for value in [&mut url.username, &mut url.password] {
+                    if !value.is_empty() {
+                        *value = options.mask.clone();
+                    }
+                }
+            }
+        }
+
+        if let Some(query) = &url.query {
+            url.query = Some(options.redact_query(query));
+        }
+
+        url.to_string()
+    }
+
+    /// https://url.spec.whatwg.org/#url-path-serializer
+    pub fn pathname(&self) -> String {
+        // If url has an opaque path, then return url’s path.
+        if self.has_opaque_path {
+            return self.path.concat();
+        }
+
+        let mut output = String::new();
+
+        // For each segment of url’s path: append U+002F (/) followed by segment to output.
+        for segment in &self.path {
+            output.push('/');
+            output += segment;
+        }
+
+        output
+    }
+
+    /// https://url.spec.whatwg.org/#concept-url-origin
+    ///
+    /// Blob URL entries are not supported, so blob URLs always get the origin of the URL in their path.
+    pub fn origin(&self) -> Origin {
+        match self.scheme_type {
+            // "blob"
+            SchemeType::NotSpecial if self.scheme == "blob" => {
+                // Let pathURL be the result of parsing the result of URL path serializing url.
+                let machine = URLStateMachine::new(&self.pathname(), None, None, None);
+
+                // If pathURL is failure, then return a new opaque origin.
+                // If pathURL’s scheme is "http", "https", or "file", then return pathURL’s origin.
+                match machine.url.scheme_type {
+                    SchemeType::Http | SchemeType::Https | SchemeType::File if !machine.failure => {
+                        machine.url.origin()
+                    }
+                    // Return a new opaque origin.
+                    _ => Origin::Opaque(OpaqueOrigin::new()),
+                }
+            }
+            // "ftp", "http", "https", "ws", "wss"
+            // Return the tuple origin (url’s scheme, url’s host, url’s port, null).
+            SchemeType::Ftp
+            | SchemeType::Http
+            | SchemeType::Https
+            | SchemeType::Ws
+            | SchemeType::Wss => match self.parsed_host() {
+                Some(host) => Origin::Tuple(TupleOrigin {
+                    scheme: self.scheme.clone(),
+                    host,
+                    port: self.port.as_ref().and_then(|port| port.parse().ok()),
+                    domain: None,
+                }),
+                None => Origin::Opaque(OpaqueOrigin::new()),
+            },
+            // "file": Unfortunate as it is, this is left as an exercise to the reader.
+            // When in doubt, return a new opaque origin.
+            // Otherwise: Return a new opaque origin.
+            SchemeType::File | SchemeType::NotSpecial => Origin::Opaque(OpaqueOrigin::new()),
+        }
+    }
+
+    /// https://w3c.github.io/webappsec-secure-contexts/#is-url-trustworthy
+    pub fn is_potentially_trustworthy(&self) -> bool {
+        TrustworthyOrigins::default().is_url_potentially_trustworthy(self)
+    }
+
+    /// The host, as it was parsed. Returns `None` if the host is null or the empty string.
+    pub fn parsed_host(&self) -> Option<Host> {
+        let host = self.host.as_ref().filter(|host| !host.is_empty())?;
+
+        if let Some(address) = host
+            .strip_prefix('[')
+            .and_then(|host| host.strip_suffix(']'))
+        {
+            return parse_ipv6_with_zone_id(address).ok();
+        }
+
+        if !self.scheme_type.is_special() {
+            return Some(Host::Opaque(host.clone()));
+        }
+
+        if ends_with_a_number(host) {
+            return parse_ipv4(host.clone()).map(Host::Ipv4);
+        }
+
+        Some(Host::Domain(host.clone()))
+    }
+
+    /// The port, or else the default port of the scheme: of a special scheme, or of a scheme in
+    /// `KNOWN_DEFAULT_PORTS`. Schemes registered with a `SchemeRegistry` are not looked up, use
+    /// `port_or_default_in` for those.
+    pub fn port_or_known_default(&self) -> Option<u16> {
+        match &self.port {
+            Some(port) => port.parse().ok(),
+            None => self
+                .scheme_type
+                .default_port()
+                .or_else(|| known_default_port(&self.scheme)),
+        }
+    }
+
+    /// The port, or else the default port of the scheme: of a special scheme, or of a scheme in
+    /// the registry.
+    pub fn port_or_default_in(&self, registry: &SchemeRegistry) -> Option<u16> {
+        registry.port_or_default(self)
+    }
+
+    /// The addresses to connect to, using the port or else the default port of the scheme.
+    /// IP addresses are returned as they are, and domains are looked up with the resolver.
+    pub fn socket_addrs<R: Resolver>(
+        &self,
+        resolver: &R,
+    ) -> Result<Vec<SocketAddr>, SocketAddrsError<R::Error>> {
+        let host = self.parsed_host().ok_or(SocketAddrsError::MissingHost)?;
+
+        let port = match &self.port {
+            Some(port) => port.parse::<u16>().ok(),
+            None => self.scheme_type.default_port(),
+        }
+        .ok_or(SocketAddrsError::MissingPort)?;
+
+        match host {
+            Host::Ipv4(address) => Ok(vec![SocketAddr::new(IpAddr::V4(address), port)]),
+            Host::Ipv6(address) => Ok(vec![SocketAddr::new(IpAddr::V6(address), port)]),
+            // Only numeric zone identifiers can be used as a scope id without asking the system.
+            Host::Ipv6WithZone(address, zone) => match zone.parse() {
+                Ok(scope_id) => Ok(vec![SocketAddr::V6(SocketAddrV6::new(
+                    address, port, 0, scope_id,
+                ))]),
+                Err(_) => Err(SocketAddrsError::UnsupportedZone(zone)),
+            },
+            Host::Domain(domain) => resolver
+                .resolve(&domain, port)
+                .map_err(SocketAddrsError::Resolve),
+            Host::Opaque(_) => Err(SocketAddrsError::OpaqueHost),
+        }
+    }
+
+    /// The public suffix of the host, using the bundled list. Returns `None` unless the host is a domain.
+    #[cfg(feature = "std")]
+    pub fn public_suffix(&self) -> Option<&str> {
+        PublicSuffixList::bundled().public_suffix(self.domain()?)
+    }
+
+    /// The registrable domain of the host, using the bundled list. Returns `None` unless the host is
+    /// a domain with a label in front of its public suffix.
+    #[cfg(feature = "std")]
+    pub fn registrable_domain(&self) -> Option<&str> {
+        PublicSuffixList::bundled().registrable_domain(self.domain()?)
+    }
+
+    /// The host, if it is a domain.
+    #[cfg(feature = "std")]
+    fn domain(&self) -> Option<&str> {
+        match self.parsed_host()? {
+            Host::Domain(_) => self.host.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// https://url.spec.whatwg.org/#concept-url-serializer
+impl core::fmt::Display for URL {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Let output be url’s scheme and U+003A (:) concatenated.
+        write!(f, "{}:", self.scheme)?;
+
+        // If url’s host is non-null:
+        if let Some(host) = &self.host {
+            // Append "//" to output.
+            f.write_str("//")?;
+
+            // If url includes credentials, then:
+            if !self.username.is_empty() || !self.password.is_empty() {
+                // Append url’s username to output.
+                f.write_str(&self.username)?;
+
+                // If url’s password is not the empty string, then append U+003A (:), followed by url’s password, to output.
+                if !self.password.is_empty() {
+                    write!(f, ":{}", self.password)?;
+                }
+
+                // Append U+0040 (@) to output.
+                f.write_str("@")?;
+            }
+
+            // Append url’s host, serialized, to output.
+            f.write_str(host)?;
+
+            // If url’s port is non-null, append U+003A (:) followed by url’s port, serialized, to output.
+            if let Some(port) = &self.port {
+                write!(f, ":{}", port)?;
+            }
+        }
+
+        // If url’s host is null, url does not have an opaque path, url’s path’s size is greater than 1,
+        // and url’s path[0] is the empty string, then append U+002F (/) followed by U+002E (.) to output.
+        if self.host.is_none()
+            && !self.has_opaque_path
+            && self.path.len() > 1
+            && self.path[0].is_empty()
+        {
+            f.write_str("/.")?;
+        }
+
+        // Append the result of URL path serializing url to output.
+        f.write_str(&self.pathname())?;
+
+        // If url’s query is non-null, append U+003F (?), followed by url’s query, to output.
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+
+        // If exclude fragment is false and url’s fragment is non-null, then append U+0023 (#), followed by url’s fragment, to output.
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An "http" URL with the address as its host and the path "/".
+impl From<IpAddr> for URL {
+    fn from(address: IpAddr) -> Self {
+        URL {
+            scheme: "http".to_string(),
+            scheme_type: SchemeType::Http,
+            host: Some(Host::from(address).to_string()),
+            path: vec!["".to_string()],
+            ..URL::new()
+        }
+    }
+}
+
+/// An "http" URL with the address as its host and port, and the path "/". The IPv6 scope id is dropped.
+impl From<SocketAddr> for URL {
+    fn from(address: SocketAddr) -> Self {
+        let mut url = URL::from(address.ip());
+
+        if Some(address.port()) != url.scheme_type.default_port() {
+            url.port = Some(address.port().to_string());
+        }
+
+        url
+    }
 }
@@ -0,0 +1,235 @@
+use crate::machine::URLStateMachine;
+use crate::parser::ends_with_a_number;
+use crate::scheme::SchemeType;
+use crate::string::{is_double_dot_path_segment, is_single_dot_path_segment};
+use crate::url::URL;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+/// A read-only view of a parsed URL that borrows from its input.
+///
+/// When the input is already a valid, normalized URL every component is a slice of the input.
+/// Otherwise the input goes through the `URLStateMachine` and the components are owned.
+#[derive(Clone, Debug)]
+pub struct UrlRef<'a> {
+    scheme: Cow<'a, str>,
+    scheme_type: SchemeType,
+    username: Cow<'a, str>,
+    password: Cow<'a, str>,
+    host: Option<Cow<'a, str>>,
+    port: Option<Cow<'a, str>>,
+    path: Vec<Cow<'a, str>>,
+    has_opaque_path: bool,
+    query: Option<Cow<'a, str>>,
+    fragment: Option<Cow<'a, str>>,
+}
+
+impl<'a> UrlRef<'a> {
+    /// Returns `None` if the input is not a valid URL.
+    pub fn parse(input: &'a str) -> Option<UrlRef<'a>> {
+        if let Some(url) = UrlRef::parse_normalized(input) {
+            return Some(url);
+        }
+
+        let machine = URLStateMachine::new(input, None, None, None);
+
+        if machine.failure {
+            return None;
+        }
+
+        Some(UrlRef::from(machine.url))
+    }
+
+    pub fn scheme(&self) -> Cow<'a, str> {
+        self.scheme.clone()
+    }
+
+    pub fn scheme_type(&self) -> SchemeType {
+        self.scheme_type
+    }
+
+    pub fn username(&self) -> Cow<'a, str> {
+        self.username.clone()
+    }
+
+    pub fn password(&self) -> Cow<'a, str> {
+        self.password.clone()
+    }
+
+    pub fn host(&self) -> Option<Cow<'a, str>> {
+        self.host.clone()
+    }
+
+    pub fn port(&self) -> Option<Cow<'a, str>> {
+        self.port.clone()
+    }
+
+    pub fn path(&self) -> &[Cow<'a, str>] {
+        &self.path
+    }
+
+    pub fn has_opaque_path(&self) -> bool {
+        self.has_opaque_path
+    }
+
+    pub fn query(&self) -> Option<Cow<'a, str>> {
+        self.query.clone()
+    }
+
+    pub fn fragment(&self) -> Option<Cow<'a, str>> {
+        self.fragment.clone()
+    }
+
+    /// Returns true if no component had to be normalized.
+    pub fn is_borrowed(&self) -> bool {
+        let is_borrowed = |value: &Cow<'a, str>| matches!(value, Cow::Borrowed(_));
+
+        is_borrowed(&self.scheme)
+            && is_borrowed(&self.username)
+            && is_borrowed(&self.password)
+            && self.host.iter().all(is_borrowed)
+            && self.port.iter().all(is_borrowed)
+            && self.path.iter().all(is_borrowed)
+            && self.query.iter().all(is_borrowed)
+            && self.fragment.iter().all(is_borrowed)
+    }
+
+    pub fn into_owned(self) -> URL {
+        URL {
+            scheme: self.scheme.into_owned(),
+            scheme_type: self.scheme_type,
+            username: self.username.into_owned(),
+            password: self.password.into_owned(),
+            host: self.host.map(Cow::into_owned),
+            port: self.port.map(Cow::into_owned),
+            path: self.path.into_iter().map(Cow::into_owned).collect(),
+            has_opaque_path: self.has_opaque_path,
+            query: self.query.map(Cow::into_owned),
+            fragment: self.fragment.map(Cow::into_owned),
+        }
+    }
+}
+
+impl<'a> From<URL> for UrlRef<'a> {
+    fn from(url: URL) -> Self {
+        UrlRef {
+            scheme: Cow::Owned(url.scheme),
+            scheme_type: url.scheme_type,
+            username: Cow::Owned(url.username),
+            password: Cow::Owned(url.password),
+            host: url.host.map(Cow::Owned),
+            port: url.port.map(Cow::Owned),
+            path: url.path.into_iter().map(Cow::Owned).collect(),
+            has_opaque_path: url.has_opaque_path,
+            query: url.query.map(Cow::Owned),
+            fragment: url.fragment.map(Cow::Owned),
+        }
+    }
+}
+
+impl<'a> UrlRef<'a> {
+    /// Recognizes `scheme://host[:port][/path][?query][#fragment]` inputs of a special, non-file scheme
+    /// that the URL parser would return unchanged. Anything it is unsure about is left to the parser.
+    fn parse_normalized(input: &'a str) -> Option<UrlRef<'a>> {
+        // Inputs with non-ASCII, C0 control or space code points, or backslashes are never normalized.
+        if input.bytes().any(|c| c <= b' ' || c >= 0x7F || c == b'\\') {
+            return None;
+        }
+
+        let (scheme, rest) = input.split_once("://")?;
+        let scheme_type = SchemeType::from(scheme);
+
+        if !scheme_type.is_special() || scheme_type.is_file() {
+            return None;
+        }
+
+        let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        let (authority, rest) = rest.split_at(authority_end);
+
+        let (host, port) = match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        };
+
+        if !is_normalized_domain(host) {
+            return None;
+        }
+
+        if let Some(port) = port {
+            let value = port.parse::<u16>().ok()?;
+
+            // Signs, leading zeroes and default ports are all rewritten by the port state.
+            if !port.bytes().all(|c| c.is_ascii_digit())
+                || (port.len() > 1 && port.starts_with('0'))
+                || Some(value) == scheme_type.default_port()
+            {
+                return None;
+            }
+        }
+
+        let (rest, fragment) = match rest.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (rest, None),
+        };
+
+        let (path, query) = match rest.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (rest, None),
+        };
+
+        // A special URL without a path is given the path "/".
+        let path = if path.is_empty() { "/" } else { path };
+
+        // The path percent-encode set, minus the code points rejected above.
+        if path.contains(['"', '<', '>', '`', '{', '}']) {
+            return None;
+        }
+
+        let segments: Vec<Cow<'a, str>> = path[1..].split('/').map(Cow::Borrowed).collect();
+
+        if segments.iter().any(|segment| {
+            let segment = segment.to_lowercase();
+            is_single_dot_path_segment(&segment) || is_double_dot_path_segment(&segment)
+        }) {
+            return None;
+        }
+
+        // The special-query percent-encode set, minus the code points rejected above.
+        if query.is_some_and(|query| query.contains(['"', '<', '>', '\''])) {
+            return None;
+        }
+
+        // The fragment percent-encode set, minus the code points rejected above.
+        if fragment.is_some_and(|fragment| fragment.contains(['"', '<', '>', '`'])) {
+            return None;
+        }
+
+        Some(UrlRef {
+            scheme: Cow::Borrowed(scheme),
+            scheme_type,
+            username: Cow::Borrowed(""),
+            password: Cow::Borrowed(""),
+            host: Some(Cow::Borrowed(host)),
+            port: port.map(Cow::Borrowed),
+            path: segments,
+            has_opaque_path: false,
+            query: query.map(Cow::Borrowed),
+            fragment: fragment.map(Cow::Borrowed),
+        })
+    }
+}
+
+/// Returns true if domain to ASCII would return the domain unchanged and it is not an IPv4 address.
+fn is_normalized_domain(domain: &str) -> bool {
+    !domain.is_empty()
+        && !ends_with_a_number(domain)
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with("xn--")
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'-')
+        })
+}
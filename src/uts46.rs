@@ -0,0 +1,473 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use idna::uts46::{AsciiDenyList, DnsLength, Hyphens, Uts46};
+use idna_adapter::{Adapter, FIRST_BC_MASK, LAST_LTR_MASK, MIDDLE_LTR_MASK, RTL_MASK};
+
+/// Processing options for UTS #46 ToASCII: https://www.unicode.org/reports/tr46/#ToASCII
+///
+/// The URL Standard sets CheckBidi and CheckJoiners to true, and so do both presets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Uts46Options {
+    pub check_hyphens: bool,
+    pub check_bidi: bool,
+    pub check_joiners: bool,
+    pub use_std3_ascii_rules: bool,
+    pub transitional_processing: bool,
+    pub verify_dns_length: bool,
+}
+
+impl Default for Uts46Options {
+    /// The options used by domain to ASCII with beStrict set to false.
+    fn default() -> Self {
+        Uts46Options {
+            check_hyphens: false,
+            check_bidi: true,
+            check_joiners: true,
+            use_std3_ascii_rules: false,
+            transitional_processing: false,
+            verify_dns_length: false,
+        }
+    }
+}
+
+impl Uts46Options {
+    /// The options used by domain to ASCII with beStrict set to true.
+    pub fn strict() -> Self {
+        Uts46Options {
+            check_hyphens: true,
+            check_bidi: true,
+            check_joiners: true,
+            use_std3_ascii_rules: true,
+            transitional_processing: false,
+            verify_dns_length: true,
+        }
+    }
+
+    fn ascii_deny_list(&self) -> AsciiDenyList {
+        if self.use_std3_ascii_rules {
+            AsciiDenyList::STD3
+        } else {
+            AsciiDenyList::EMPTY
+        }
+    }
+
+    fn hyphens(&self) -> Hyphens {
+        if self.check_hyphens {
+            Hyphens::Check
+        } else {
+            Hyphens::Allow
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IdnaErrorKind {
+    /// An "xn--" label that is not valid Punycode, or decodes to an invalid label.
+    Punycode,
+    /// A label that fails the CONTEXTJ rules for U+200C and U+200D.
+    Joiners,
+    /// A label that fails the Bidi Rule of RFC 5893.
+    Bidi,
+    /// A hyphen in the first, last, or both third and fourth positions of a label.
+    Hyphens,
+    /// An ASCII code point outside of letters, digits and hyphen while UseSTD3ASCIIRules is set.
+    Std3Ascii,
+    /// A disallowed code point, or a label that starts with a combining mark.
+    DisallowedCodePoint,
+    /// An empty label while VerifyDnsLength is set.
+    EmptyLabel,
+    /// A label longer than 63 octets while VerifyDnsLength is set.
+    LabelTooLong,
+    /// A domain longer than 253 octets while VerifyDnsLength is set.
+    DomainTooLong,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdnaError {
+    /// The index of the offending label, or `None` for errors about the domain as a whole.
+    pub label: Option<usize>,
+    pub kind: IdnaErrorKind,
+}
+
+/// UTS #46 ToASCII with the given options, reporting every error found per label.
+pub fn domain_to_ascii(domain: &str, options: &Uts46Options) -> Result<String, Vec<IdnaError>> {
+    let domain = map_transitional(domain, options.transitional_processing);
+    let uts46 = Uts46::new();
+
+    let result = uts46.to_ascii(
+        domain.as_bytes(),
+        options.ascii_deny_list(),
+        options.hyphens(),
+        DnsLength::Ignore,
+    );
+
+    let ascii = match result {
+        Ok(ascii) => ascii.into_owned(),
+        Err(_) => process_marked_labels(&uts46, &domain, options)?,
+    };
+
+    if options.verify_dns_length {
+        let errors = dns_length_errors(&ascii);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+    }
+
+    Ok(ascii)
+}
+
+/// idna always applies CheckBidi and CheckJoiners, and marks each error it finds with U+FFFD.
+/// The marks are traced back to the mapped code points they replaced, so that every label with
+/// an error is reported, and labels whose only errors come from checks that are turned off are
+/// converted as if those checks had passed.
+fn process_marked_labels(
+    uts46: &Uts46,
+    domain: &str,
+    options: &Uts46Options,
+) -> Result<String, Vec<IdnaError>> {
+    let adapter = Adapter::new();
+    let (marked, _) = uts46.to_unicode(
+        domain.as_bytes(),
+        options.ascii_deny_list(),
+        options.hyphens(),
+    );
+
+    let mapped: String = adapter.map_normalize(domain.chars()).collect();
+    let mapped_labels: Vec<&str> = mapped.split('.').collect();
+
+    let mut labels = Vec::new();
+    let mut errors = Vec::new();
+
+    // idna writes ASCII labels that pass its other checks as they were given, without the marks
+    // of the Bidi Rule, so those labels are checked again here.
+    let is_bidi_domain = marked
+        .chars()
+        .chain(mapped.chars())
+        .any(|c| adapter.bidi_class(c).to_mask().intersects(RTL_MASK));
+
+    for (index, label) in marked.split('.').enumerate() {
+        if !label.contains('\u{FFFD}') {
+            if options.check_bidi
+                && is_bidi_domain
+                && label.is_ascii()
+                && !satisfies_ltr_bidi_rule(&adapter, label)
+            {
+                errors.push(IdnaError {
+                    label: Some(index),
+                    kind: IdnaErrorKind::Bidi,
+                });
+            }
+
+            labels.push(String::from(label));
+            continue;
+        }
+
+        let reference = match mapped_labels.get(index) {
+            Some(mapped) => reference_label(&adapter, mapped),
+            None => Err(IdnaErrorKind::DisallowedCodePoint),
+        };
+
+        match reference.and_then(|reference| check_marks(&adapter, label, reference, options)) {
+            Ok(reference) => labels.push(reference),
+            Err(kind) => errors.push(IdnaError {
+                label: Some(index),
+                kind,
+            }),
+        }
+    }
+
+    // With every check turned on, idna failing means an error was found above. This is only a
+    // safeguard, so that a failure is never reported without an error.
+    if errors.is_empty() && options.check_bidi && options.check_joiners {
+        errors.push(IdnaError {
+            label: None,
+            kind: IdnaErrorKind::DisallowedCodePoint,
+        });
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut ascii = Vec::with_capacity(labels.len());
+
+    for (index, label) in labels.iter().enumerate() {
+        if label.is_ascii() {
+            ascii.push(label.clone());
+            continue;
+        }
+
+        match idna::punycode::encode_str(label) {
+            Some(encoded) => ascii.push(format!("xn--{}", encoded)),
+            None => errors.push(IdnaError {
+                label: Some(index),
+                kind: IdnaErrorKind::LabelTooLong,
+            }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(ascii.join("."))
+    } else {
+        Err(errors)
+    }
+}
+
+/// The mapped label without any marks, decoding "xn--" labels as idna does.
+fn reference_label(adapter: &Adapter, mapped: &str) -> Result<Vec<char>, IdnaErrorKind> {
+    let Some(punycode) = mapped.strip_prefix("xn--") else {
+        return Ok(mapped.chars().collect());
+    };
+
+    let decoded = idna::punycode::decode(punycode).ok_or(IdnaErrorKind::Punycode)?;
+
+    if decoded.iter().all(char::is_ascii)
+        || !adapter
+            .normalize_validate(decoded.iter().copied())
+            .eq(decoded.iter().copied())
+    {
+        return Err(IdnaErrorKind::Punycode);
+    }
+
+    Ok(decoded)
+}
+
+/// Returns the unmarked label if every mark in it comes from a check that is turned off, or else
+/// the kind of the first error.
+fn check_marks(
+    adapter: &Adapter,
+    marked: &str,
+    reference: Vec<char>,
+    options: &Uts46Options,
+) -> Result<String, IdnaErrorKind> {
+    let marked: Vec<char> = marked.chars().collect();
+
+    if marked.len() != reference.len() {
+        return Err(IdnaErrorKind::DisallowedCodePoint);
+    }
+
+    for (index, _) in marked.iter().enumerate().filter(|(_, c)| **c == '\u{FFFD}') {
+        let kind = mark_kind(adapter, &reference, index, options);
+
+        let ignored = match kind {
+            IdnaErrorKind::Joiners => !options.check_joiners,
+            IdnaErrorKind::Bidi => !options.check_bidi,
+            _ => false,
+        };
+
+        if !ignored {
+            return Err(kind);
+        }
+    }
+
+    Ok(reference.into_iter().collect())
+}
+
+/// https://www.rfc-editor.org/rfc/rfc5893#section-2, rules 1, 5 and 6, for a label without
+/// right-to-left code points.
+fn satisfies_ltr_bidi_rule(adapter: &Adapter, label: &str) -> bool {
+    let mut classes = label.chars().map(|c| adapter.bidi_class(c).to_mask());
+
+    let Some(first) = classes.next() else {
+        return true;
+    };
+
+    let last = classes.clone().next_back().unwrap_or(first);
+
+    first.intersects(FIRST_BC_MASK)
+        && classes.all(|class| class.intersects(MIDDLE_LTR_MASK))
+        && last.intersects(LAST_LTR_MASK)
+}
+
+/// The check that marked the code point at `index`, following the order idna checks a label in.
+fn mark_kind(
+    adapter: &Adapter,
+    label: &[char],
+    index: usize,
+    options: &Uts46Options,
+) -> IdnaErrorKind {
+    let c = label[index];
+    let is_hyphen_position = index == 0
+        || index == label.len() - 1
+        || ((index == 2 || index == 3) && label[2] == '-' && label[3] == '-');
+
+    match c {
+        '\u{FFFD}' => IdnaErrorKind::DisallowedCodePoint,
+        '-' if options.check_hyphens && is_hyphen_position => IdnaErrorKind::Hyphens,
+        c if options.use_std3_ascii_rules
+            && c.is_ascii()
+            && !c.is_ascii_alphanumeric()
+            && c != '-' =>
+        {
+            IdnaErrorKind::Std3Ascii
+        }
+        c if index == 0 && adapter.is_mark(c) => IdnaErrorKind::DisallowedCodePoint,
+        '\u{200C}' | '\u{200D}' => IdnaErrorKind::Joiners,
+        // Everything else idna marks is a code point that breaks the Bidi Rule.
+        _ => IdnaErrorKind::Bidi,
+    }
+}
+
+/// Decides which Unicode labels are safe to show to users. Labels it rejects are kept in Punycode.
+pub trait DisplayPolicy {
+    /// Called with each valid, non-ASCII label and the top-level label of its domain.
+    fn show_as_unicode(&self, label: &str, tld: &str) -> bool;
+}
+
+impl<F: Fn(&str, &str) -> bool> DisplayPolicy for F {
+    fn show_as_unicode(&self, label: &str, tld: &str) -> bool {
+        self(label, tld)
+    }
+}
+
+/// The default display policy. It keeps Punycode for labels that mix the Latin, Greek and
+/// Cyrillic scripts, and for Cyrillic labels made only of Latin look-alikes under a non-Cyrillic
+/// top-level domain, as in "аррӏе.com".
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SafeDisplayPolicy;
+
+impl DisplayPolicy for SafeDisplayPolicy {
+    fn show_as_unicode(&self, label: &str, tld: &str) -> bool {
+        let mut scripts = label.chars().filter_map(confusable_script);
+        let first = scripts.next();
+
+        if scripts.any(|script| Some(script) != first) {
+            return false;
+        }
+
+        let is_cyrillic_tld = tld
+            .chars()
+            .any(|c| confusable_script(c) == Some(Script::Cyrillic));
+
+        !(first == Some(Script::Cyrillic)
+            && !is_cyrillic_tld
+            && label
+                .chars()
+                .all(|c| c == '-' || c.is_ascii_digit() || "аԁеһіјӏорԛѕсуԝх".contains(c)))
+    }
+}
+
+/// UTS #46 ToUnicode, for display only. Labels with errors, and labels the policy deems unsafe,
+/// are returned as they were given.
+pub fn domain_to_unicode(domain: &str, policy: &impl DisplayPolicy) -> String {
+    let uts46 = Uts46::new();
+
+    let (unicode, result) = uts46.to_user_interface(
+        domain.as_bytes(),
+        AsciiDenyList::EMPTY,
+        Hyphens::Allow,
+        |label, tld, _| {
+            let label: String = label.iter().collect();
+            let tld: String = tld.iter().collect();
+            policy.show_as_unicode(&label, &tld)
+        },
+    );
+
+    if result.is_ok() {
+        return unicode.into_owned();
+    }
+
+    // Errors are marked with U+FFFD, so keep the labels that had them as they were.
+    domain
+        .split(is_label_separator)
+        .zip(unicode.split('.'))
+        .map(|(input, output)| {
+            if output.contains('\u{FFFD}') {
+                input
+            } else {
+                output
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join(".")
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+}
+
+/// The scripts whose letters are most often confused with one another.
+fn confusable_script(c: char) -> Option<Script> {
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' => {
+            Some(Script::Latin)
+        }
+        '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Some(Script::Greek),
+        '\u{0400}'..='\u{052F}' => Some(Script::Cyrillic),
+        _ => None,
+    }
+}
+
+/// https://www.unicode.org/reports/tr46/#ToASCII, step 4.
+fn dns_length_errors(ascii: &str) -> Vec<IdnaError> {
+    let mut errors = Vec::new();
+
+    // The root label and its dot are not counted.
+    let domain = ascii.strip_suffix('.').unwrap_or(ascii);
+
+    // The length of the domain name, excluding the root label and its dot, is from 1 to 253.
+    if domain.len() > 253 {
+        errors.push(IdnaError {
+            label: None,
+            kind: IdnaErrorKind::DomainTooLong,
+        });
+    }
+
+    // The length of each label is from 1 to 63.
+    for (index, label) in domain.split('.').enumerate() {
+        let kind = if label.is_empty() {
+            IdnaErrorKind::EmptyLabel
+        } else if label.len() > 63 {
+            IdnaErrorKind::LabelTooLong
+        } else {
+            continue;
+        };
+
+        errors.push(IdnaError {
+            label: Some(index),
+            kind,
+        });
+    }
+
+    errors
+}
+
+/// Transitional processing maps deviation characters before the rest of UTS #46 processing.
+fn map_transitional(domain: &str, transitional: bool) -> Cow<'_, str> {
+    if !transitional || !domain.contains(['ß', 'ẞ', 'ς', '\u{200C}', '\u{200D}']) {
+        return Cow::Borrowed(domain);
+    }
+
+    let mut output = String::with_capacity(domain.len());
+
+    for c in domain.chars() {
+        match c {
+            'ß' | 'ẞ' => output.push_str("ss"),
+            'ς' => output.push('σ'),
+            '\u{200C}' | '\u{200D}' => {}
+            _ => output.push(c),
+        }
+    }
+
+    Cow::Owned(output)
+}
+
+/// U+002E (.) and the code points UTS #46 maps to it.
+fn is_label_separator(c: char) -> bool {
+    matches!(c, '.' | '\u{3002}' | '\u{FF0E}' | '\u{FF61}')
+}
+
+impl core::fmt::Display for IdnaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.label {
+            Some(label) => write!(f, "label {}: {:?}", label, self.kind),
+            None => write!(f, "{:?}", self.kind),
+        }
+    }
+}
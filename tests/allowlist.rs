@@ -0,0 +1,101 @@
+use url_wasm::allowlist::{HostAllowlist, HostPattern, PatternError, PatternErrorKind};
+use url_wasm::machine::URLStateMachine;
+use url_wasm::url::URL;
+
+fn url(input: &str) -> URL {
+    let machine = URLStateMachine::new(input, None, None, None);
+    assert!(!machine.failure, "{}", input);
+    machine.url
+}
+
+#[test]
+fn domain_patterns() {
+    let list = HostAllowlist::parse([
+        "*.example.com",
+        ".example.org",
+        "API.example.net",
+        "*.Bücher.example",
+    ])
+    .unwrap();
+
+    assert!(list.allows(&url("https://www.example.com/")));
+    assert!(list.allows(&url("https://a.b.example.com./")));
+    assert!(!list.allows(&url("https://example.com/")));
+    assert!(!list.allows(&url("https://badexample.com/")));
+
+    assert!(list.allows(&url("https://example.org/")));
+    assert!(list.allows(&url("https://www.example.org/")));
+    assert!(!list.allows(&url("https://example.org.evil.com/")));
+
+    assert!(list.allows(&url("https://api.example.net/")));
+    assert!(!list.allows(&url("https://www.api.example.net/")));
+
+    assert!(list.allows(&url("https://shop.xn--bcher-kva.example/")));
+    assert!(list.allows(&url("https://shop.BÜCHER.example/")));
+
+    assert!(!list.allows(&url("foo://www.example.com/")));
+}
+
+#[test]
+fn ip_patterns() {
+    let list = HostAllowlist::parse(["10.0.0.0/8", "fe80::/10", "[::1]", "192.168.1.1"]).unwrap();
+
+    assert!(list.allows(&url("http://10.1.2.3/")));
+    assert!(list.allows(&url("http://0xa.1/")));
+    assert!(list.allows(&url("http://[::ffff:10.0.0.1]/")));
+    assert!(!list.allows(&url("http://11.0.0.1/")));
+    assert!(list.allows(&url("http://[fe80::1234]/")));
+    assert!(!list.allows(&url("http://[fec0::1]/")));
+    assert!(list.allows(&url("http://[0:0::1]/")));
+    assert!(list.allows(&url("http://3232235777/")));
+    assert!(!list.allows(&url("http://10.example.com/")));
+
+    // 6to4 and NAT64 addresses lead elsewhere than the IPv4 address they embed.
+    for input in [
+        "http://[2002:a00:1::]/",
+        "http://[64:ff9b::a00:1]/",
+        "http://[2002:c0a8:101::]/",
+        "http://[64:ff9b::c0a8:101]/",
+        "http://[::a00:1]/",
+    ] {
+        assert!(!list.allows(&url(input)), "{}", input);
+    }
+    assert!(list.allows(&url("http://[::ffff:192.168.1.1]/")));
+
+    let exact = HostAllowlist::parse(["1.2.3.4"]).unwrap();
+    assert!(exact.allows(&url("http://[::ffff:102:304]/")));
+    assert!(!exact.allows(&url("http://[64:ff9b::102:304]/")));
+    assert!(!exact.allows(&url("http://[2002:102:304::]/")));
+
+    assert_eq!(
+        HostPattern::parse("0.0.0.0/0"),
+        Ok(HostPattern::Cidr("0.0.0.0".parse().unwrap(), 0))
+    );
+}
+
+#[test]
+fn invalid_patterns() {
+    assert_eq!(
+        HostAllowlist::parse(["example.com", "a.*.example.com"]),
+        Err(PatternError {
+            index: 1,
+            kind: PatternErrorKind::Wildcard
+        })
+    );
+    assert_eq!(
+        HostPattern::parse("*.10.0.0.1"),
+        Err(PatternErrorKind::Wildcard)
+    );
+    assert_eq!(
+        HostPattern::parse("10.0.0.0/33"),
+        Err(PatternErrorKind::Cidr)
+    );
+    assert_eq!(
+        HostPattern::parse("example.com/8"),
+        Err(PatternErrorKind::Cidr)
+    );
+    assert!(matches!(
+        HostPattern::parse("exa mple.com"),
+        Err(PatternErrorKind::Host(_))
+    ));
+}
@@ -0,0 +1,67 @@
+use url_wasm::batch::Parser;
+
+#[test]
+fn resolves_against_shared_base() {
+    let mut parser = Parser::with_base("http://example.com/a/b?q#f").unwrap();
+    let urls = parser.parse_many(["c", "/d", "//other.org/x", "?y", "#g", "../e"]);
+
+    let summary: Vec<(String, Vec<String>)> = urls
+        .into_iter()
+        .map(|url| {
+            let url = url.unwrap();
+            (url.host.unwrap(), url.path)
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "example.com".to_string(),
+                vec!["a".to_string(), "c".to_string()]
+            ),
+            ("example.com".to_string(), vec!["d".to_string()]),
+            ("other.org".to_string(), vec!["x".to_string()]),
+            (
+                "example.com".to_string(),
+                vec!["a".to_string(), "b".to_string()]
+            ),
+            (
+                "example.com".to_string(),
+                vec!["a".to_string(), "b".to_string()]
+            ),
+            ("example.com".to_string(), vec!["e".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn reports_failures_per_input() {
+    let mut parser = Parser::new(None);
+    let urls = parser.parse_many(["relative", "https://example.com/", "http://[::1"]);
+
+    assert!(urls[0].is_none());
+    assert_eq!(
+        urls[1].as_ref().unwrap().host.as_deref(),
+        Some("example.com")
+    );
+    assert!(urls[2].is_none());
+}
+
+#[test]
+fn rejects_invalid_base() {
+    assert!(Parser::with_base("not a url").is_none());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_matches_sequential() {
+    let mut parser = Parser::with_base("https://example.com/dir/").unwrap();
+    let inputs: Vec<String> = (0..1000).map(|i| format!("page-{}?n={}", i, i)).collect();
+    let inputs: Vec<&str> = inputs.iter().map(String::as_str).collect();
+
+    let parallel = parser.par_parse_many(&inputs);
+    let sequential = parser.parse_many(inputs.iter().copied());
+
+    assert_eq!(format!("{:?}", parallel), format!("{:?}", sequential));
+}
@@ -0,0 +1,90 @@
+use url_wasm::cookie::{
+    cookie_domain, cookie_path, default_path, domain_match, path_match, CookieDomain,
+    CookieDomainError,
+};
+use url_wasm::machine::URLStateMachine;
+use url_wasm::psl::PublicSuffixList;
+use url_wasm::url::URL;
+
+fn url(input: &str) -> URL {
+    let machine = URLStateMachine::new(input, None, None, None);
+    assert!(!machine.failure, "{}", input);
+    machine.url
+}
+
+#[test]
+fn matching() {
+    assert!(domain_match("www.example.com", "example.com"));
+    assert!(domain_match("example.com", "example.com"));
+    assert!(!domain_match("badexample.com", "example.com"));
+    assert!(!domain_match("1.2.3.4", "2.3.4"));
+
+    assert!(path_match("/docs/web", "/docs"));
+    assert!(path_match("/docs/", "/docs/"));
+    assert!(path_match("/docs/web", "/"));
+    assert!(!path_match("/docsets", "/docs"));
+    assert!(!path_match("/", "/docs"));
+}
+
+#[test]
+fn paths() {
+    assert_eq!(default_path(&url("https://example.com")), "/");
+    assert_eq!(default_path(&url("https://example.com/a")), "/");
+    assert_eq!(default_path(&url("https://example.com/a/b/c?x=/y")), "/a/b");
+    assert_eq!(default_path(&url("https://example.com/a/")), "/a");
+
+    let request = url("https://example.com/a/b");
+    assert_eq!(cookie_path(&request, Some("/x")), "/x");
+    assert_eq!(cookie_path(&request, Some("x")), "/a");
+    assert_eq!(cookie_path(&request, None), "/a");
+}
+
+#[test]
+fn domains() {
+    let list = PublicSuffixList::parse("com\nco.uk\ngithub.io");
+    let request = url("https://www.Example.com/");
+
+    assert_eq!(
+        cookie_domain(&request, None, &list),
+        Ok(CookieDomain::HostOnly("www.example.com".to_string()))
+    );
+    assert_eq!(
+        cookie_domain(&request, Some(".EXAMPLE.com"), &list),
+        Ok(CookieDomain::Domain("example.com".to_string()))
+    );
+    assert_eq!(
+        cookie_domain(&request, Some("com"), &list),
+        Err(CookieDomainError::PublicSuffix)
+    );
+    assert_eq!(
+        cookie_domain(&request, Some("other.com"), &list),
+        Err(CookieDomainError::NotDomainMatch)
+    );
+    assert_eq!(
+        cookie_domain(&url("https://github.io/"), Some("github.io"), &list),
+        Ok(CookieDomain::HostOnly("github.io".to_string()))
+    );
+
+    let domain = cookie_domain(&request, Some("example.com"), &list).unwrap();
+    assert!(domain.matches("api.example.com"));
+    assert!(!domain.matches("example.org"));
+}
+
+#[test]
+fn ip_hosts() {
+    let list = PublicSuffixList::parse("com");
+    let request = url("http://0x7f.1/");
+
+    assert_eq!(
+        cookie_domain(&request, Some("127.0.0.1"), &list),
+        Ok(CookieDomain::HostOnly("127.0.0.1".to_string()))
+    );
+    assert_eq!(
+        cookie_domain(&request, Some("0.1"), &list),
+        Err(CookieDomainError::IpAddress)
+    );
+    assert_eq!(
+        cookie_domain(&url("http://[::1]/"), Some("[::1]"), &list),
+        Ok(CookieDomain::HostOnly("[::1]".to_string()))
+    );
+}
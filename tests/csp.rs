@@ -0,0 +1,216 @@
+use url_wasm::csp::{HostSource, PortPart, SourceExpression, SourceList};
+use url_wasm::machine::URLStateMachine;
+use url_wasm::origin::Origin;
+use url_wasm::url::URL;
+
+fn parse(input: &str) -> URL {
+    let machine = URLStateMachine::new(input, None, None, None);
+    assert!(!machine.failure, "{}", input);
+    machine.url
+}
+
+fn matches(list: &str, url: &str, origin: &str) -> bool {
+    let origin: Origin = parse(origin).origin();
+    SourceList::parse(list).matches(&parse(url), &origin, 0)
+}
+
+#[test]
+fn parses_source_expressions() {
+    assert_eq!(
+        SourceList::parse(" 'SELF'  https:\t*.example.com:* example.net/a/ 'nonce-abc' a_b ")
+            .expressions(),
+        &[
+            SourceExpression::SelfOrigin,
+            SourceExpression::Scheme("https".to_string()),
+            SourceExpression::Host(HostSource {
+                scheme: None,
+                host: "*.example.com".to_string(),
+                port: Some(PortPart::Any),
+                path: None,
+            }),
+            SourceExpression::Host(HostSource {
+                scheme: None,
+                host: "example.net".to_string(),
+                port: None,
+                path: Some("/a/".to_string()),
+            }),
+            SourceExpression::Other("'nonce-abc'".to_string()),
+            SourceExpression::Other("a_b".to_string()),
+        ]
+    );
+    assert_eq!(
+        SourceExpression::parse("WSS://cdn.example.com:8443"),
+        SourceExpression::Host(HostSource {
+            scheme: Some("wss".to_string()),
+            host: "cdn.example.com".to_string(),
+            port: Some(PortPart::Port(8443)),
+            path: None,
+        })
+    );
+    assert_eq!(
+        SourceExpression::parse("a.*.com"),
+        SourceExpression::Other("a.*.com".to_string())
+    );
+}
+
+#[test]
+fn empty_and_none() {
+    let origin = "https://example.com/";
+
+    assert!(!matches("", "https://example.com/", origin));
+    assert!(!matches("'none'", "https://example.com/", origin));
+    assert!(matches("'none' 'self'", "https://example.com/", origin));
+    assert!(!matches("'nonce-abc'", "https://example.com/", origin));
+}
+
+#[test]
+fn wildcard_and_scheme_sources() {
+    let origin = "https://example.com/";
+
+    assert!(matches("*", "http://other.test/", origin));
+    assert!(!matches("*", "data:text/plain,a", origin));
+    assert!(!matches("*", "ws://example.com/", origin));
+    assert!(matches("*", "wss://example.com/", "wss://example.com/"));
+    assert!(matches("data:", "data:text/plain,a", origin));
+    assert!(matches("HTTP:", "https://other.test/", origin));
+    assert!(!matches("https:", "http://other.test/", origin));
+    assert!(matches("ws:", "https://other.test/", origin));
+}
+
+#[test]
+fn host_sources() {
+    let origin = "http://example.com/";
+
+    assert!(matches("*.example.com", "http://a.b.example.com/", origin));
+    assert!(!matches("*.example.com", "http://example.com/", origin));
+    assert!(matches(
+        "CDN.example.com",
+        "http://cdn.EXAMPLE.com/",
+        origin
+    ));
+    assert!(matches(
+        "cdn.example.com",
+        "https://cdn.example.com/",
+        origin
+    ));
+    assert!(!matches(
+        "cdn.example.com",
+        "http://cdn.example.com/",
+        "https://example.com/"
+    ));
+    assert!(matches(
+        "http://cdn.example.com",
+        "https://cdn.example.com/",
+        origin
+    ));
+    assert!(!matches("*", "ftp://example.com/", origin));
+    assert!(!matches("127.0.0.1", "http://127.0.0.1/", origin));
+    assert!(matches("*", "http://[::1]/", origin));
+}
+
+#[test]
+fn ports() {
+    let origin = "https://example.com/";
+
+    assert!(matches("example.com:443", "https://example.com/", origin));
+    assert!(matches("example.com", "https://example.com/", origin));
+    assert!(!matches("example.com", "https://example.com:8443/", origin));
+    assert!(matches(
+        "example.com:*",
+        "https://example.com:8443/",
+        origin
+    ));
+    assert!(matches(
+        "example.com:8443",
+        "https://example.com:8443/",
+        origin
+    ));
+    assert!(!matches("example.com:80", "https://example.com/", origin));
+}
+
+#[test]
+fn paths() {
+    let origin = "https://example.com/";
+    let list = SourceList::parse("example.com/scripts/ example.com/lib.js");
+    let origin_value = parse(origin).origin();
+
+    assert!(matches(
+        "example.com/scripts/",
+        "https://example.com/scripts/a/b.js",
+        origin
+    ));
+    assert!(!matches(
+        "example.com/scripts/",
+        "https://example.com/scripts",
+        origin
+    ));
+    assert!(matches(
+        "example.com/lib.js",
+        "https://example.com/lib.js?v=1",
+        origin
+    ));
+    assert!(!matches(
+        "example.com/lib.js",
+        "https://example.com/lib.js/x",
+        origin
+    ));
+    assert!(matches(
+        "example.com/a%20b",
+        "https://example.com/a b",
+        origin
+    ));
+    assert!(matches(
+        "example.com/",
+        "https://example.com/anything",
+        origin
+    ));
+
+    // Paths are ignored after a redirect, to avoid leaking the target of cross-origin redirects.
+    assert!(!list.matches(&parse("https://example.com/other.js"), &origin_value, 0));
+    assert!(list.matches(&parse("https://example.com/other.js"), &origin_value, 1));
+}
+
+#[test]
+fn self_allows_secure_upgrades() {
+    assert!(matches(
+        "'self'",
+        "https://example.com/a",
+        "https://example.com/"
+    ));
+    assert!(matches(
+        "'self'",
+        "wss://example.com/",
+        "https://example.com/"
+    ));
+    assert!(matches(
+        "'self'",
+        "https://example.com/",
+        "http://example.com/"
+    ));
+    assert!(matches(
+        "'self'",
+        "ws://example.com/",
+        "http://example.com/"
+    ));
+    assert!(!matches(
+        "'self'",
+        "http://example.com/",
+        "https://example.com/"
+    ));
+    assert!(!matches(
+        "'self'",
+        "ws://example.com/",
+        "https://example.com/"
+    ));
+    assert!(!matches(
+        "'self'",
+        "https://example.com:8443/",
+        "https://example.com/"
+    ));
+    assert!(!matches(
+        "'self'",
+        "https://other.com/",
+        "https://example.com/"
+    ));
+    assert!(!matches("'self'", "data:,a", "data:,a"));
+}
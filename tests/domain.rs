@@ -0,0 +1,54 @@
+use url_wasm::domain::{special_use_domain, SpecialUseDomain};
+use url_wasm::host::{parse_host, Host};
+use url_wasm::uts46::Uts46Options;
+
+fn host(input: &str) -> Host {
+    parse_host(input, false, &Uts46Options::default()).unwrap()
+}
+
+#[test]
+fn special_use_hosts() {
+    assert_eq!(
+        host("LocalHost").special_use_domain(),
+        Some(SpecialUseDomain::Localhost)
+    );
+    assert_eq!(
+        host("api.localhost.").special_use_domain(),
+        Some(SpecialUseDomain::Localhost)
+    );
+    assert_eq!(
+        host("printer.local").special_use_domain(),
+        Some(SpecialUseDomain::Local)
+    );
+    assert_eq!(
+        host("www.example.com").special_use_domain(),
+        Some(SpecialUseDomain::Example)
+    );
+    assert_eq!(
+        host("router.home.arpa").special_use_domain(),
+        Some(SpecialUseDomain::HomeArpa)
+    );
+    assert_eq!(host("127.0.0.1").special_use_domain(), None);
+}
+
+#[test]
+fn matches_whole_labels() {
+    assert_eq!(special_use_domain("notlocalhost"), None);
+    assert_eq!(special_use_domain("localhost.com"), None);
+    assert_eq!(special_use_domain("myexample.com"), None);
+    assert_eq!(special_use_domain("arpa"), None);
+    assert_eq!(special_use_domain("."), None);
+    assert_eq!(special_use_domain("a.TEST"), Some(SpecialUseDomain::Test));
+    assert_eq!(
+        special_use_domain("x.onion."),
+        Some(SpecialUseDomain::Onion)
+    );
+    assert_eq!(
+        special_use_domain("corp.internal"),
+        Some(SpecialUseDomain::Internal)
+    );
+    assert_eq!(
+        special_use_domain("never.invalid"),
+        Some(SpecialUseDomain::Invalid)
+    );
+}
@@ -0,0 +1,159 @@
+use url_wasm::hsts::{HstsError, HstsStore, PreloadError};
+use url_wasm::machine::URLStateMachine;
+use url_wasm::url::URL;
+
+fn parse(input: &str) -> URL {
+    let machine = URLStateMachine::new(input, None, None, None);
+    assert!(!machine.failure, "{}", input);
+    machine.url
+}
+
+fn upgrade(store: &HstsStore, input: &str, now: u64) -> String {
+    let mut url = parse(input);
+    store.upgrade(&mut url, now);
+    url.to_string()
+}
+
+const PRELOAD: &str = "
+# Preloaded hosts
+bank.example includeSubDomains
+Bücher.example
+";
+
+#[test]
+fn preload_list() {
+    let mut store = HstsStore::new();
+    store.load_preload_list(PRELOAD).unwrap();
+
+    assert_eq!(
+        upgrade(&store, "http://bank.example/", 0),
+        "https://bank.example/"
+    );
+    assert_eq!(
+        upgrade(&store, "http://login.BANK.example./a?b#c", 0),
+        "https://login.bank.example./a?b#c"
+    );
+    assert_eq!(
+        upgrade(&store, "http://xn--bcher-kva.example/", 0),
+        "https://xn--bcher-kva.example/"
+    );
+    assert_eq!(
+        upgrade(&store, "http://www.xn--bcher-kva.example/", 0),
+        "http://www.xn--bcher-kva.example/"
+    );
+    assert_eq!(
+        upgrade(&store, "http://notbank.example/", 0),
+        "http://notbank.example/"
+    );
+
+    assert_eq!(
+        HstsStore::new().load_preload_list("ok.example\nbad example.com\n"),
+        Err(PreloadError { line: 2 })
+    );
+    assert_eq!(
+        HstsStore::new().load_preload_list("127.0.0.1"),
+        Err(PreloadError { line: 1 })
+    );
+}
+
+#[test]
+fn upgrades_schemes_and_ports() {
+    let mut store = HstsStore::new();
+    store.load_preload_list("example.com").unwrap();
+
+    assert_eq!(
+        upgrade(&store, "ws://example.com/chat", 0),
+        "wss://example.com/chat"
+    );
+    assert_eq!(
+        upgrade(&store, "http://example.com:80/", 0),
+        "https://example.com/"
+    );
+    assert_eq!(
+        upgrade(&store, "http://example.com:443/", 0),
+        "https://example.com/"
+    );
+    assert_eq!(
+        upgrade(&store, "http://example.com:8080/", 0),
+        "https://example.com:8080/"
+    );
+    assert_eq!(
+        upgrade(&store, "ftp://example.com/", 0),
+        "ftp://example.com/"
+    );
+
+    let mut url = parse("https://example.com/");
+    assert!(!store.upgrade(&mut url, 0));
+}
+
+#[test]
+fn header_processing() {
+    let mut store = HstsStore::new();
+    let secure = parse("https://example.com/");
+
+    assert_eq!(
+        store.process_header(&parse("http://example.com/"), "max-age=60", 0),
+        Err(HstsError::InsecureTransport)
+    );
+    assert_eq!(
+        store.process_header(&parse("https://127.0.0.1/"), "max-age=60", 0),
+        Err(HstsError::NotDomain)
+    );
+    for value in [
+        "",
+        "includeSubDomains",
+        "max-age=abc",
+        "max-age=-1",
+        "max-age=1; max-age=2",
+        "max-age=1; includeSubDomains; includesubdomains",
+        "max-age=1; includeSubDomains=yes",
+    ] {
+        assert_eq!(
+            store.process_header(&secure, value, 0),
+            Err(HstsError::InvalidHeader),
+            "{}",
+            value
+        );
+    }
+
+    store
+        .process_header(
+            &secure,
+            " Max-Age=\"100\" ; includeSubDomains; preload",
+            1000,
+        )
+        .unwrap();
+    assert_eq!(
+        store.find("a.example.com", 1000).unwrap().expires,
+        Some(1100)
+    );
+    assert_eq!(
+        upgrade(&store, "http://a.example.com/", 1099),
+        "https://a.example.com/"
+    );
+    assert_eq!(
+        upgrade(&store, "http://a.example.com/", 1100),
+        "http://a.example.com/"
+    );
+
+    store.process_header(&secure, "max-age=100", 2000).unwrap();
+    assert!(store.find("example.com", 2000).is_some());
+    assert!(store.find("a.example.com", 2000).is_none());
+
+    store.process_header(&secure, "max-age=0", 2000).unwrap();
+    assert!(store.find("example.com", 2000).is_none());
+}
+
+#[test]
+fn headers_do_not_change_preloaded_entries() {
+    let mut store = HstsStore::new();
+    store.load_preload_list(PRELOAD).unwrap();
+
+    store
+        .process_header(&parse("https://bank.example/"), "max-age=0", 0)
+        .unwrap();
+    assert_eq!(
+        upgrade(&store, "http://bank.example/", u64::MAX),
+        "https://bank.example/"
+    );
+}
@@ -0,0 +1,131 @@
+use std::net::{IpAddr, Ipv4Addr};
+use url_wasm::host::Host;
+use url_wasm::ip::{canonical, special_purpose, SpecialPurpose};
+use url_wasm::machine::URLStateMachine;
+
+fn host(input: &str) -> Host {
+    URLStateMachine::new(input, None, None, None)
+        .url
+        .parsed_host()
+        .unwrap()
+}
+
+fn purpose(address: &str) -> Option<SpecialPurpose> {
+    special_purpose(address.parse().unwrap())
+}
+
+#[test]
+fn loopback_in_every_form() {
+    for input in [
+        "http://127.0.0.1/",
+        "http://127.1.2.3/",
+        "http://0x7f.1/",
+        "http://017700000001/",
+        "http://2130706433/",
+        "http://[::1]/",
+        "http://[::ffff:127.0.0.1]/",
+    ] {
+        assert!(host(input).is_loopback(), "{}", input);
+        assert!(host(input).reaches_this_host(), "{}", input);
+    }
+
+    for input in [
+        "http://0.0.0.0/",
+        "http://0.1.2.3/",
+        "http://[::]/",
+        "http://[::ffff:0.0.0.0]/",
+        "http://[64:ff9b::7f00:1]/",
+    ] {
+        assert!(!host(input).is_loopback(), "{}", input);
+        assert!(host(input).reaches_this_host(), "{}", input);
+    }
+
+    for input in [
+        "http://localhost/",
+        "http://[::ffff:8.8.8.8]/",
+        "http://[2002:7f00:1::]/",
+    ] {
+        assert!(!host(input).is_loopback(), "{}", input);
+        assert!(!host(input).reaches_this_host(), "{}", input);
+    }
+
+    assert_eq!(host("http://[::ffff:8.8.8.8]/").special_purpose(), None);
+}
+
+#[test]
+fn six_to_four_is_its_own_block() {
+    assert_eq!(purpose("2002:7f00:1::"), Some(SpecialPurpose::SixToFour));
+    assert_eq!(purpose("2002:a00:1::"), Some(SpecialPurpose::SixToFour));
+    assert_eq!(purpose("2002:808:808::"), Some(SpecialPurpose::SixToFour));
+    assert_eq!(
+        host("http://[2002:7f00:1::]/").special_purpose(),
+        Some(SpecialPurpose::SixToFour)
+    );
+}
+
+#[test]
+fn canonical_agrees_with_special_purpose() {
+    for address in [
+        "2002:7f00:1::",
+        "2002:a00:1::",
+        "::ffff:127.0.0.1",
+        "::ffff:0:a00:1",
+        "64:ff9b::a00:1",
+        "fe80::1",
+        "127.0.0.1",
+    ] {
+        let address: IpAddr = address.parse().unwrap();
+        assert_eq!(
+            special_purpose(canonical(address)),
+            special_purpose(address),
+            "{}",
+            address
+        );
+    }
+
+    let six_to_four: IpAddr = "2002:7f00:1::".parse().unwrap();
+    assert_eq!(canonical(six_to_four), six_to_four);
+}
+
+#[test]
+fn ipv4_blocks() {
+    assert_eq!(purpose("10.1.2.3"), Some(SpecialPurpose::Private));
+    assert_eq!(purpose("172.31.0.1"), Some(SpecialPurpose::Private));
+    assert_eq!(purpose("172.32.0.1"), None);
+    assert_eq!(purpose("192.168.1.1"), Some(SpecialPurpose::Private));
+    assert_eq!(
+        purpose("100.64.0.1"),
+        Some(SpecialPurpose::SharedAddressSpace)
+    );
+    assert_eq!(purpose("100.128.0.1"), None);
+    assert_eq!(purpose("169.254.169.254"), Some(SpecialPurpose::LinkLocal));
+    assert_eq!(purpose("192.0.2.1"), Some(SpecialPurpose::Documentation));
+    assert_eq!(purpose("198.19.0.1"), Some(SpecialPurpose::Benchmarking));
+    assert_eq!(purpose("239.255.255.250"), Some(SpecialPurpose::Multicast));
+    assert_eq!(purpose("255.255.255.255"), Some(SpecialPurpose::Broadcast));
+    assert_eq!(purpose("240.0.0.1"), Some(SpecialPurpose::Reserved));
+    assert_eq!(purpose("8.8.8.8"), None);
+}
+
+#[test]
+fn ipv6_blocks() {
+    assert_eq!(purpose("::"), Some(SpecialPurpose::Unspecified));
+    assert_eq!(purpose("fe80::1"), Some(SpecialPurpose::LinkLocal));
+    assert_eq!(purpose("fd00::1"), Some(SpecialPurpose::UniqueLocal));
+    assert_eq!(purpose("ff02::1"), Some(SpecialPurpose::Multicast));
+    assert_eq!(purpose("2001:db8::1"), Some(SpecialPurpose::Documentation));
+    assert_eq!(purpose("3fff::1"), Some(SpecialPurpose::Documentation));
+    assert_eq!(purpose("3fff:fff::1"), Some(SpecialPurpose::Documentation));
+    assert_eq!(purpose("3fff:1000::1"), None);
+    assert_eq!(purpose("3ff0::1"), None);
+    assert_eq!(purpose("3ff1::1"), None);
+    assert_eq!(purpose("2001:2::1"), Some(SpecialPurpose::Benchmarking));
+    assert_eq!(purpose("100::1"), Some(SpecialPurpose::DiscardOnly));
+    assert_eq!(purpose("::ffff:10.0.0.1"), Some(SpecialPurpose::Private));
+    assert_eq!(purpose("2606:4700::1111"), None);
+
+    assert_eq!(
+        canonical("::ffff:0:a9fe:a9fe".parse().unwrap()),
+        IpAddr::V4(Ipv4Addr::new(169, 254, 169, 254))
+    );
+}
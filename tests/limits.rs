@@ -0,0 +1,162 @@
+use url_wasm::machine::{LimitExceeded, ParseLimits, ParseOptions, URLStateMachine};
+
+fn parse(input: &str, limits: ParseLimits) -> Result<String, Option<LimitExceeded>> {
+    let options = ParseOptions::default().limits(limits);
+    let machine = URLStateMachine::with_options(input, None, options);
+
+    if machine.failure {
+        Err(machine.limit_exceeded)
+    } else {
+        Ok(machine.url.to_string())
+    }
+}
+
+#[test]
+fn unlimited_by_default() {
+    let input = format!(
+        "https://example.com/{}?{}",
+        "a/".repeat(10_000),
+        "q".repeat(10_000)
+    );
+
+    assert!(parse(&input, ParseLimits::default()).is_ok());
+    assert_eq!(parse("https://[::1", ParseLimits::default()), Err(None));
+}
+
+#[test]
+fn input_length() {
+    let limits = ParseLimits::default().max_input_length(24);
+
+    assert!(parse("https://example.com/abcd", limits).is_ok());
+    assert_eq!(
+        parse("https://example.com/abcde", limits),
+        Err(Some(LimitExceeded::InputLength))
+    );
+    assert_eq!(
+        parse(&" ".repeat(25), limits),
+        Err(Some(LimitExceeded::InputLength))
+    );
+}
+
+#[test]
+fn path_segments() {
+    let limits = ParseLimits::default().max_path_segments(3);
+
+    assert_eq!(
+        parse("https://example.com/a/b/c", limits),
+        Ok("https://example.com/a/b/c".to_string())
+    );
+    assert_eq!(
+        parse("https://example.com/a/b/c/", limits),
+        Err(Some(LimitExceeded::PathSegments))
+    );
+    assert_eq!(
+        parse("https://example.com/a/b/../../c/d/e", limits),
+        Ok("https://example.com/c/d/e".to_string())
+    );
+    assert_eq!(
+        parse(
+            &format!("https://example.com{}", "/".repeat(1_000_000)),
+            limits
+        ),
+        Err(Some(LimitExceeded::PathSegments))
+    );
+}
+
+#[test]
+fn host_and_label_length() {
+    let limits = ParseLimits::default()
+        .max_host_length(20)
+        .max_label_length(8);
+
+    assert!(parse("https://abcdefgh.example/", limits).is_ok());
+    assert_eq!(
+        parse("https://abcdefghi.example/", limits),
+        Err(Some(LimitExceeded::LabelLength))
+    );
+    assert_eq!(
+        parse("https://abcd.efgh.ijkl.example/", limits),
+        Err(Some(LimitExceeded::HostLength))
+    );
+    // The limits apply to the serialized host, after Punycode encoding.
+    assert_eq!(
+        parse("https://bücherei.de/", limits),
+        Err(Some(LimitExceeded::LabelLength))
+    );
+    assert_eq!(
+        parse("file://abcdefghijk/", limits),
+        Err(Some(LimitExceeded::LabelLength))
+    );
+    assert_eq!(
+        parse("foo://abcdefghijk:1/", limits),
+        Err(Some(LimitExceeded::LabelLength))
+    );
+}
+
+#[test]
+fn host_length_is_checked_before_host_parsing() {
+    let limits = ParseLimits::default().max_host_length(10);
+
+    // Longer as written than serialized.
+    assert_eq!(
+        parse("https://%61%62%63.example/", limits),
+        Err(Some(LimitExceeded::HostLength))
+    );
+    assert_eq!(
+        parse("https://ab.example/", limits),
+        Ok("https://ab.example/".to_string())
+    );
+
+    let hostile = format!("https://{}/", "ü".repeat(1_000_000));
+    assert_eq!(
+        parse(&hostile, limits),
+        Err(Some(LimitExceeded::HostLength))
+    );
+    assert_eq!(
+        parse(&format!("file://{}/", "a".repeat(11)), limits),
+        Err(Some(LimitExceeded::HostLength))
+    );
+}
+
+#[test]
+fn query_length() {
+    let limits = ParseLimits::default().max_query_length(6);
+
+    assert!(parse("https://example.com/?abcdef#fragment", limits).is_ok());
+    assert_eq!(
+        parse("https://example.com/?abcdefg", limits),
+        Err(Some(LimitExceeded::QueryLength))
+    );
+    // "é" becomes "%C3%A9".
+    assert_eq!(
+        parse("https://example.com/?aé", limits),
+        Err(Some(LimitExceeded::QueryLength))
+    );
+}
+
+#[test]
+fn percent_encoded_expansion() {
+    let limits = ParseLimits::default().max_percent_encoded_expansion(8);
+
+    // Each of these adds 2 bytes per encoded byte, 8 in total.
+    assert!(parse("https://us er@example.com/ a b#c d", limits).is_ok());
+    assert!(parse("https://example.com/?\"\"#  ", limits).is_ok());
+    assert!(parse("https://u:p@example.com/é?é", limits).is_ok());
+    assert!(parse("mailto: a\u{7f}\u{7f}\u{7f}\u{7f}", limits).is_ok());
+
+    for input in [
+        "https://example.com/     x",
+        "https://\u{3000}\u{3000}@example.com/",
+        "https://example.com/?\"\"\"\"\"",
+        "https://example.com/#\u{3000}\u{3000}",
+        "mailto:\u{7f}\u{7f}\u{7f}\u{7f}\u{7f}",
+        "https://example.com/a b?c d#e f g h",
+    ] {
+        assert_eq!(
+            parse(input, limits),
+            Err(Some(LimitExceeded::PercentEncodedExpansion)),
+            "{}",
+            input
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+use url_wasm::host::{parse_host, Host};
+use url_wasm::machine::{ParseOptions, URLStateMachine};
+use url_wasm::net::{Resolver, SocketAddrsError};
+use url_wasm::url::URL;
+use url_wasm::uts46::Uts46Options;
+
+struct FakeResolver(HashMap<&'static str, IpAddr>);
+
+impl Resolver for FakeResolver {
+    type Error = &'static str;
+
+    fn resolve(&self, domain: &str, port: u16) -> Result<Vec<SocketAddr>, Self::Error> {
+        self.0
+            .get(domain)
+            .map(|address| vec![SocketAddr::new(*address, port)])
+            .ok_or("not found")
+    }
+}
+
+fn parse(input: &str) -> URL {
+    let machine = URLStateMachine::new(input, None, None, None);
+    assert!(!machine.failure, "{}", input);
+    machine.url
+}
+
+#[test]
+fn ip_hosts() {
+    let options = Uts46Options::default();
+
+    assert_eq!(
+        parse_host("0x7f.1", false, &options),
+        Ok(Host::Ipv4(Ipv4Addr::new(127, 0, 0, 1)))
+    );
+    assert_eq!(
+        parse_host("[1:0:0:2::3]", false, &options),
+        Ok(Host::Ipv6(Ipv6Addr::new(1, 0, 0, 2, 0, 0, 0, 3)))
+    );
+
+    let url = parse("http://[::ffff:1.2.3.4]:8080/");
+    assert_eq!(url.host.as_deref(), Some("[::ffff:102:304]"));
+    assert_eq!(
+        url.parsed_host().and_then(|host| host.ip()),
+        Some(IpAddr::V6(Ipv4Addr::new(1, 2, 3, 4).to_ipv6_mapped()))
+    );
+
+    assert_eq!(
+        parse("http://4294967295/").parsed_host(),
+        Some(Host::Ipv4(Ipv4Addr::BROADCAST))
+    );
+    assert_eq!(
+        parse("foo://1.2.3.4/").parsed_host(),
+        Some(Host::Opaque("1.2.3.4".to_string()))
+    );
+}
+
+#[test]
+fn from_std_net() {
+    assert_eq!(
+        Host::from(IpAddr::V6(Ipv6Addr::LOCALHOST)).to_string(),
+        "[::1]"
+    );
+
+    let url = URL::from(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080));
+    assert_eq!(url.scheme, "http");
+    assert_eq!(url.host.as_deref(), Some("127.0.0.1"));
+    assert_eq!(url.port.as_deref(), Some("8080"));
+
+    let url = URL::from(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 80));
+    assert_eq!(url.host.as_deref(), Some("[::1]"));
+    assert_eq!(url.port, None);
+
+    assert_eq!(
+        Host::from(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080)),
+        Host::Ipv4(Ipv4Addr::LOCALHOST)
+    );
+    assert_eq!(
+        Host::from(SocketAddr::from(SocketAddrV6::new(
+            Ipv6Addr::LOCALHOST,
+            443,
+            0,
+            0
+        ))),
+        Host::Ipv6(Ipv6Addr::LOCALHOST)
+    );
+
+    let host = Host::from(SocketAddr::from(SocketAddrV6::new(
+        "fe80::1".parse().unwrap(),
+        8080,
+        0,
+        3,
+    )));
+    assert_eq!(host.to_string(), "[fe80::1%253]");
+
+    let input = format!("http://{}:8080/", host);
+    let options = ParseOptions::default().ipv6_zone_id(true);
+    let machine = URLStateMachine::with_options(&input, None, options);
+    assert_eq!(
+        machine.url.socket_addrs(&FakeResolver(HashMap::new())),
+        Ok(vec!["[fe80::1%3]:8080".parse().unwrap()])
+    );
+}
+
+#[test]
+fn socket_addrs() {
+    let resolver = FakeResolver(HashMap::from([(
+        "example.com",
+        IpAddr::V4(Ipv4Addr::new(93, 184, 215, 14)),
+    )]));
+
+    assert_eq!(
+        parse("https://example.com/").socket_addrs(&resolver),
+        Ok(vec!["93.184.215.14:443".parse().unwrap()])
+    );
+    assert_eq!(
+        parse("http://[::1]:8080/").socket_addrs(&resolver),
+        Ok(vec!["[::1]:8080".parse().unwrap()])
+    );
+    assert_eq!(
+        parse("https://example.org/").socket_addrs(&resolver),
+        Err(SocketAddrsError::Resolve("not found"))
+    );
+    assert_eq!(
+        parse("redis://127.0.0.1/").socket_addrs(&resolver),
+        Err(SocketAddrsError::MissingPort)
+    );
+    assert_eq!(
+        parse("redis://cache:6379/").socket_addrs(&resolver),
+        Err(SocketAddrsError::OpaqueHost)
+    );
+}
+
+#[test]
+fn ipv6_zone_id() {
+    let options = ParseOptions::default().ipv6_zone_id(true);
+
+    assert!(URLStateMachine::new("http://[fe80::1%25eth0]/", None, None, None).failure);
+
+    let machine = URLStateMachine::with_options("http://[FE80::1%25eth0]:8080/", None, options);
+    assert!(!machine.failure);
+    assert_eq!(machine.url.host.as_deref(), Some("[fe80::1%25eth0]"));
+    assert_eq!(
+        machine.url.parsed_host(),
+        Some(Host::Ipv6WithZone(
+            "fe80::1".parse().unwrap(),
+            "eth0".to_string()
+        ))
+    );
+
+    let machine = URLStateMachine::with_options("http://[fe80::1%253]/", None, options);
+    assert_eq!(
+        machine.url.socket_addrs(&FakeResolver(HashMap::new())),
+        Ok(vec!["[fe80::1%3]:80".parse().unwrap()])
+    );
+
+    let machine = URLStateMachine::with_options("http://[fe80::1%25eth0]/", None, options);
+    assert_eq!(
+        machine.url.socket_addrs(&FakeResolver(HashMap::new())),
+        Err(SocketAddrsError::UnsupportedZone("eth0".to_string()))
+    );
+
+    for input in [
+        "http://[fe80::1%eth0]/",
+        "http://[fe80::1%25]/",
+        "http://[fe80::1%25eth 0]/",
+        "http://[fe80::1%25%2]/",
+    ] {
+        assert!(
+            URLStateMachine::with_options(input, None, options).failure,
+            "{}",
+            input
+        );
+    }
+
+    let machine = URLStateMachine::with_options("http://[::1]/", None, options);
+    assert_eq!(machine.url.host.as_deref(), Some("[::1]"));
+}
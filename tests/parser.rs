@@ -0,0 +1,136 @@
+use url_wasm::machine::URLStateMachine;
+
+/// Parses `input` against `base` as the URL Standard's URL parser does, returning the href, or
+/// `None` on failure.
+fn href(input: &str, base: Option<&str>) -> Option<String> {
+    let base = base.map(|base| {
+        let machine = URLStateMachine::new(base, None, None, None);
+        assert!(!machine.failure, "{}", base);
+        machine.url
+    });
+
+    let machine = URLStateMachine::new(input, base.as_ref(), None, None);
+
+    if machine.failure {
+        None
+    } else {
+        Some(machine.url.to_string())
+    }
+}
+
+/// Each case is an input, a base, and the expected href, in the format of urltestdata.json from
+/// web-platform-tests, where a missing href means the input fails to parse.
+fn check(cases: &[(&str, Option<&str>, Option<&str>)]) {
+    for (input, base, expected) in cases {
+        assert_eq!(
+            href(input, *base).as_deref(),
+            *expected,
+            "input: {:?}, base: {:?}",
+            input,
+            base
+        );
+    }
+}
+
+#[test]
+fn opaque_paths() {
+    check(&[
+        ("sc:abc", None, Some("sc:abc")),
+        ("data:text/html,test", None, Some("data:text/html,test")),
+        ("mailto:a b", None, Some("mailto:a b")),
+        ("sc:\u{0001}x", None, Some("sc:%01x")),
+        ("sc:abc#", None, Some("sc:abc#")),
+        ("sc:abc?q#f", None, Some("sc:abc?q#f")),
+    ]);
+}
+
+#[test]
+fn file_hosts() {
+    check(&[
+        ("file://localhost/x", None, Some("file:///x")),
+        ("file://example.com/x", None, Some("file://example.com/x")),
+        ("file://EXAMPLE.com?q", None, Some("file://example.com/?q")),
+        ("file://h\u{00FC}/x", None, Some("file://xn--h-eha/x")),
+        ("file://C:/x", None, Some("file:///C:/x")),
+    ]);
+}
+
+#[test]
+fn no_scheme() {
+    check(&[
+        ("b", Some("http://h/a"), Some("http://h/b")),
+        ("?q", Some("http://h/a"), Some("http://h/a?q")),
+        ("b", Some("file:///a"), Some("file:///b")),
+        ("#x", Some("sc:abc"), Some("sc:abc#x")),
+        ("#", Some("sc:abc?q"), Some("sc:abc?q#")),
+        ("x", Some("sc:abc"), None),
+        ("?x", Some("sc:abc"), None),
+        ("b", None, None),
+        ("#x", None, None),
+    ]);
+}
+
+#[test]
+fn userinfo() {
+    check(&[
+        ("http://user:pass@h/", None, Some("http://user:pass@h/")),
+        (
+            "http://\u{00FC}:\u{00F6}@h/",
+            None,
+            Some("http://%C3%BC:%C3%B6@h/"),
+        ),
+        ("http://a:b:c@h/", None, Some("http://a:b%3Ac@h/")),
+        ("http://a b@h/", None, Some("http://a%20b@h/")),
+        ("http://@h/", None, Some("http://h/")),
+        ("http://@/x", None, None),
+        ("http://user@/x", None, None),
+        ("http://user:pass@/x", None, None),
+    ]);
+}
+
+#[test]
+fn empty_fragments() {
+    check(&[
+        ("http://h/p#", None, Some("http://h/p#")),
+        ("http://h/p?q#", None, Some("http://h/p?q#")),
+        ("http://h#", None, Some("http://h/#")),
+        ("#", Some("http://h/p?q"), Some("http://h/p?q#")),
+    ]);
+}
+
+#[test]
+fn ipv4_numbers() {
+    check(&[
+        ("http://0x7f.1/", None, Some("http://127.0.0.1/")),
+        ("http://0/", None, Some("http://0.0.0.0/")),
+        ("http://00/", None, Some("http://0.0.0.0/")),
+        ("http://010/", None, Some("http://0.0.0.8/")),
+        ("http://0x/", None, Some("http://0.0.0.0/")),
+        ("http://0X.0x.0/", None, Some("http://0.0.0.0/")),
+        ("http://1.2.3.4./", None, Some("http://1.2.3.4/")),
+        ("http://4294967295/", None, Some("http://255.255.255.255/")),
+        ("http://0xg/", None, Some("http://0xg/")),
+        ("http://08/", None, None),
+        ("http://09.2.3.4/", None, None),
+        ("http://4294967296/", None, None),
+        ("http://256.256.256.256/", None, None),
+        ("http://99999999999999999999999/", None, None),
+        ("http://0x100000000/", None, None),
+    ]);
+}
+
+#[test]
+fn hosts_ending_in_a_number() {
+    check(&[
+        ("http://foo.1/", None, None),
+        ("http://foo.09/", None, None),
+        ("http://foo.0x/", None, None),
+        ("http://foo.0x4/", None, None),
+        ("http://foo.1./", None, None),
+        ("http://1.foo/", None, Some("http://1.foo/")),
+        ("http://foo.0xg/", None, Some("http://foo.0xg/")),
+        ("http://foo.1a/", None, Some("http://foo.1a/")),
+        ("http://foo.09a/", None, Some("http://foo.09a/")),
+        ("foo://1.2.3.4.5/", None, Some("foo://1.2.3.4.5/")),
+    ]);
+}
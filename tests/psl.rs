@@ -0,0 +1,88 @@
+use url_wasm::machine::URLStateMachine;
+use url_wasm::psl::{PublicSuffixList, Section, Suffix};
+
+const LIST: &str = "
+// ===BEGIN ICANN DOMAINS===
+com
+uk
+co.uk
+*.ck
+!www.ck
+公司.cn
+cn
+// ===END ICANN DOMAINS===
+// ===BEGIN PRIVATE DOMAINS===
+github.io
+// ===END PRIVATE DOMAINS===
+";
+
+#[test]
+fn rules() {
+    let list = PublicSuffixList::parse(LIST);
+
+    assert_eq!(list.public_suffix("www.example.co.uk"), Some("co.uk"));
+    assert_eq!(
+        list.registrable_domain("www.example.co.uk"),
+        Some("example.co.uk")
+    );
+    assert_eq!(list.registrable_domain("co.uk"), None);
+
+    // Wildcard and exception rules.
+    assert_eq!(list.public_suffix("a.b.ck"), Some("b.ck"));
+    assert_eq!(list.registrable_domain("a.b.ck"), Some("a.b.ck"));
+    assert_eq!(list.public_suffix("www.ck"), Some("ck"));
+    assert_eq!(list.registrable_domain("a.www.ck"), Some("www.ck"));
+
+    // Unicode rules match the ASCII hosts of the URL parser.
+    assert_eq!(
+        list.public_suffix("example.xn--55qx5d.cn"),
+        Some("xn--55qx5d.cn")
+    );
+
+    // The implicit "*" rule, and trailing dots.
+    assert_eq!(
+        list.lookup("example.unknown", true),
+        Some(Suffix {
+            suffix: "unknown",
+            section: None
+        })
+    );
+    assert_eq!(
+        list.registrable_domain("www.example.com."),
+        Some("example.com.")
+    );
+    assert_eq!(list.public_suffix("a..com"), None);
+}
+
+#[test]
+fn sections() {
+    let list = PublicSuffixList::parse(LIST);
+
+    assert_eq!(
+        list.lookup("user.github.io", true),
+        Some(Suffix {
+            suffix: "github.io",
+            section: Some(Section::Private)
+        })
+    );
+    assert_eq!(
+        list.lookup("user.github.io", false),
+        Some(Suffix {
+            suffix: "io",
+            section: None
+        })
+    );
+}
+
+#[test]
+fn url_with_bundled_list() {
+    let url = URLStateMachine::new("https://www.Example.CO.uk/", None, None, None).url;
+    assert_eq!(url.public_suffix(), Some("co.uk"));
+    assert_eq!(url.registrable_domain(), Some("example.co.uk"));
+
+    let url = URLStateMachine::new("https://foo.github.io/", None, None, None).url;
+    assert_eq!(url.registrable_domain(), Some("foo.github.io"));
+
+    let url = URLStateMachine::new("http://127.0.0.1/", None, None, None).url;
+    assert_eq!(url.public_suffix(), None);
+}
@@ -0,0 +1,112 @@
+use url_wasm::machine::URLStateMachine;
+use url_wasm::registry::{known_default_port, RegisterError, SchemeRegistry, KNOWN_DEFAULT_PORTS};
+use url_wasm::url::URL;
+
+fn parse(input: &str) -> URL {
+    let machine = URLStateMachine::new(input, None, None, None);
+    assert!(!machine.failure, "{}", input);
+    machine.url
+}
+
+#[test]
+fn known_default_ports() {
+    assert!(KNOWN_DEFAULT_PORTS
+        .windows(2)
+        .all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(known_default_port("redis"), Some(6379));
+    assert_eq!(known_default_port("http"), None);
+    assert_eq!(known_default_port("unknown"), None);
+
+    assert_eq!(
+        parse("https://example.com/").port_or_known_default(),
+        Some(443)
+    );
+    assert_eq!(
+        parse("ws://example.com:8080/").port_or_known_default(),
+        Some(8080)
+    );
+    assert_eq!(parse("redis://cache/0").port_or_known_default(), Some(6379));
+    assert_eq!(
+        parse("postgres://db:5433/app").port_or_known_default(),
+        Some(5433)
+    );
+    assert_eq!(parse("myapp://host/").port_or_known_default(), None);
+    assert_eq!(parse("file:///tmp").port_or_known_default(), None);
+}
+
+#[test]
+fn registration() {
+    let mut registry = SchemeRegistry::new();
+
+    assert_eq!(registry.register("MyApp", 7000), Ok(()));
+    assert_eq!(registry.default_port("myapp"), Some(7000));
+    assert_eq!(registry.default_port("https"), Some(443));
+    assert_eq!(registry.default_port("redis"), None);
+    assert_eq!(
+        registry.register("HTTP", 8080),
+        Err(RegisterError::SpecialScheme)
+    );
+    assert_eq!(
+        registry.register("1abc", 1),
+        Err(RegisterError::InvalidScheme)
+    );
+    assert_eq!(
+        registry.register("a b", 1),
+        Err(RegisterError::InvalidScheme)
+    );
+    assert_eq!(registry.register("", 1), Err(RegisterError::InvalidScheme));
+    assert_eq!(
+        registry.port_or_default(&parse("myapp://host/")),
+        Some(7000)
+    );
+    assert_eq!(
+        parse("myapp://host/").port_or_default_in(&registry),
+        Some(7000)
+    );
+    assert_eq!(
+        parse("myapp://host:7001/").port_or_default_in(&registry),
+        Some(7001)
+    );
+    assert_eq!(parse("myapp://host/").port_or_known_default(), None);
+    assert_eq!(
+        SchemeRegistry::with_known_schemes().default_port("mysql"),
+        Some(3306)
+    );
+}
+
+#[test]
+fn elides_default_ports() {
+    let mut registry = SchemeRegistry::with_known_schemes();
+    registry.register("myapp", 7000).unwrap();
+
+    for (input, expected) in [
+        ("redis://cache:6379/0", "redis://cache/0"),
+        ("REDIS://cache:06379", "redis://cache"),
+        ("redis://cache:6380/0", "redis://cache:6380/0"),
+        (
+            "postgres://user:pass@db:5432/app",
+            "postgres://user:pass@db/app",
+        ),
+        ("myapp://host:7000/a?b#c", "myapp://host/a?b#c"),
+        ("unknown://host:7000/", "unknown://host:7000/"),
+        ("https://example.com:443/", "https://example.com/"),
+        ("http://example.com:443/", "http://example.com:443/"),
+    ] {
+        let machine = registry.parse(input, None);
+        assert!(!machine.failure, "{}", input);
+        assert_eq!(machine.url.to_string(), expected);
+    }
+
+    let base = parse("redis://cache:6379/0");
+    assert_eq!(
+        registry.parse("/1", Some(&base)).url.to_string(),
+        "redis://cache/1"
+    );
+    assert!(registry.parse("redis://[::1", None).failure);
+
+    // The parser itself is unchanged.
+    assert_eq!(
+        parse("redis://cache:6379/0").to_string(),
+        "redis://cache:6379/0"
+    );
+}
@@ -0,0 +1,32 @@
+use url_wasm::machine::URLStateMachine;
+use url_wasm::scheme::SchemeType;
+
+#[test]
+fn classifies_special_schemes() {
+    let cases = [
+        ("http://example.com", SchemeType::Http),
+        ("HTTPS://example.com", SchemeType::Https),
+        ("ws://example.com", SchemeType::Ws),
+        ("wss://example.com", SchemeType::Wss),
+        ("ftp://example.com", SchemeType::Ftp),
+        ("sc://example.com", SchemeType::NotSpecial),
+    ];
+
+    for (input, expected) in cases {
+        let machine = URLStateMachine::new(input, None, None, None);
+        assert!(!machine.failure, "{}", input);
+        assert_eq!(machine.url.scheme_type, expected, "{}", input);
+    }
+}
+
+#[test]
+fn elides_default_port() {
+    let machine = URLStateMachine::new("https://example.com:443/", None, None, None);
+    assert_eq!(machine.url.port, None);
+
+    let machine = URLStateMachine::new("wss://example.com:80/", None, None, None);
+    assert_eq!(machine.url.port, Some("80".to_string()));
+
+    let machine = URLStateMachine::new("sc://example.com:443/", None, None, None);
+    assert_eq!(machine.url.port, Some("443".to_string()));
+}
@@ -0,0 +1,72 @@
+use url_wasm::machine::URLStateMachine;
+use url_wasm::origin::{OpaqueOrigin, Origin};
+use url_wasm::trustworthy::TrustworthyOrigins;
+use url_wasm::url::URL;
+
+fn parse(input: &str) -> URL {
+    let machine = URLStateMachine::new(input, None, None, None);
+    assert!(!machine.failure, "{}", input);
+    machine.url
+}
+
+#[test]
+fn potentially_trustworthy_urls() {
+    for input in [
+        "https://example.com/",
+        "wss://example.com/",
+        "http://127.0.0.1:8080/",
+        "http://127.255.0.1/",
+        "http://0x7f.1/",
+        "http://[::1]/",
+        "http://localhost/",
+        "http://LOCALHOST./",
+        "ws://app.localhost:3000/",
+        "http://app.localhost./",
+        "file:///home/user/index.html",
+        "about:blank",
+        "about:blank#top",
+        "about:srcdoc",
+        "data:text/html,hello",
+    ] {
+        assert!(parse(input).is_potentially_trustworthy(), "{}", input);
+    }
+
+    for input in [
+        "http://example.com/",
+        "ws://example.com/",
+        "ftp://127.0.0.1.example/",
+        "http://128.0.0.1/",
+        "http://[::2]/",
+        "http://[::ffff:127.0.0.1]/",
+        "http://localhost.example/",
+        "http://notlocalhost/",
+        "about:config",
+        "javascript:alert(1)",
+        "blob:http://example.com/1",
+        "custom://localhost/",
+    ] {
+        assert!(!parse(input).is_potentially_trustworthy(), "{}", input);
+    }
+
+    assert!(parse("blob:https://example.com/1").is_potentially_trustworthy());
+}
+
+#[test]
+fn potentially_trustworthy_origins() {
+    assert!(parse("https://example.com/")
+        .origin()
+        .is_potentially_trustworthy());
+    assert!(!Origin::Opaque(OpaqueOrigin::new()).is_potentially_trustworthy());
+}
+
+#[test]
+fn configured_origins_and_schemes() {
+    let mut trusted = TrustworthyOrigins::new(vec![parse("http://dev.example:8000/").origin()]);
+
+    assert!(trusted.is_url_potentially_trustworthy(&parse("http://dev.example:8000/app")));
+    assert!(!trusted.is_url_potentially_trustworthy(&parse("http://dev.example/")));
+    assert!(!trusted.is_url_potentially_trustworthy(&parse("ftp://files.example/")));
+
+    trusted.schemes.push("ftp".to_string());
+    assert!(trusted.is_url_potentially_trustworthy(&parse("ftp://files.example/")));
+}
@@ -0,0 +1,58 @@
+use std::borrow::Cow;
+use url_wasm::machine::URLStateMachine;
+use url_wasm::url_ref::UrlRef;
+
+#[test]
+fn borrows_normalized_input() {
+    let input = "https://example.com:8443/a/b%20c?x=1&y=2#top";
+    let url = UrlRef::parse(input).unwrap();
+
+    assert!(url.is_borrowed());
+    assert_eq!(url.scheme(), "https");
+    assert_eq!(url.host(), Some(Cow::Borrowed("example.com")));
+    assert_eq!(url.port(), Some(Cow::Borrowed("8443")));
+    assert_eq!(url.path(), ["a", "b%20c"]);
+    assert_eq!(url.query(), Some(Cow::Borrowed("x=1&y=2")));
+    assert_eq!(url.fragment(), Some(Cow::Borrowed("top")));
+}
+
+#[test]
+fn falls_back_to_parser() {
+    for input in [
+        "HTTP://example.com/",
+        "http://EXAMPLE.com/",
+        "http://example.com:80/",
+        "http://example.com/a/../b",
+        "http://0x7f.1/",
+        "http://example.com/a b",
+    ] {
+        let url = UrlRef::parse(input).unwrap();
+        assert!(!url.is_borrowed(), "{}", input);
+    }
+}
+
+#[test]
+fn matches_parser_output() {
+    for input in [
+        "http://example.com",
+        "http://example.com/",
+        "https://example.com/a/b?",
+        "wss://example.com:1/#",
+        "ftp://a.b.c/d?e#f?g",
+    ] {
+        let url = UrlRef::parse(input).unwrap();
+        let machine = URLStateMachine::new(input, None, None, None);
+
+        assert!(url.is_borrowed(), "{}", input);
+        assert_eq!(
+            format!("{:?}", url.into_owned()),
+            format!("{:?}", machine.url)
+        );
+    }
+}
+
+#[test]
+fn rejects_invalid_input() {
+    assert!(UrlRef::parse("http://exa mple.com/").is_none());
+    assert!(UrlRef::parse("http://example.com:99999/").is_none());
+}
@@ -0,0 +1,300 @@
+use url_wasm::host::{parse_host, Host, HostError};
+use url_wasm::machine::URLStateMachine;
+use url_wasm::uts46::{
+    domain_to_ascii, domain_to_unicode, IdnaError, IdnaErrorKind, SafeDisplayPolicy, Uts46Options,
+};
+
+fn errors(domain: &str, options: &Uts46Options) -> Vec<IdnaError> {
+    domain_to_ascii(domain, options).unwrap_err()
+}
+
+#[test]
+fn default_options_match_url_parsing() {
+    let options = Uts46Options::default();
+
+    assert_eq!(
+        domain_to_ascii("Bücher.example", &options),
+        Ok("xn--bcher-kva.example".to_string())
+    );
+    assert_eq!(
+        domain_to_ascii("-a_b.com", &options),
+        Ok("-a_b.com".to_string())
+    );
+    assert_eq!(
+        domain_to_ascii("faß.de", &options),
+        Ok("xn--fa-hia.de".to_string())
+    );
+
+    let machine = URLStateMachine::new("http://Bücher.example/", None, None, None);
+    assert_eq!(machine.url.host.as_deref(), Some("xn--bcher-kva.example"));
+}
+
+#[test]
+fn strict_options_report_each_label() {
+    let options = Uts46Options::strict();
+
+    assert_eq!(
+        errors("-a.b_c.example", &options),
+        vec![
+            IdnaError {
+                label: Some(0),
+                kind: IdnaErrorKind::Hyphens
+            },
+            IdnaError {
+                label: Some(1),
+                kind: IdnaErrorKind::Std3Ascii
+            },
+        ]
+    );
+
+    let long_label = "a".repeat(64);
+    assert_eq!(
+        errors(&format!("ok.{}.example", long_label), &options),
+        vec![IdnaError {
+            label: Some(1),
+            kind: IdnaErrorKind::LabelTooLong
+        }]
+    );
+
+    assert_eq!(
+        errors("a..example", &options),
+        vec![IdnaError {
+            label: Some(1),
+            kind: IdnaErrorKind::EmptyLabel
+        }]
+    );
+}
+
+#[test]
+fn always_checks_bidi_joiners_and_punycode() {
+    let options = Uts46Options::default();
+
+    assert_eq!(
+        errors("ok.a\u{200D}b.com", &options),
+        vec![IdnaError {
+            label: Some(1),
+            kind: IdnaErrorKind::Joiners
+        }]
+    );
+    assert_eq!(
+        errors("xn--a.com", &options),
+        vec![IdnaError {
+            label: Some(0),
+            kind: IdnaErrorKind::Punycode
+        }]
+    );
+    assert_eq!(
+        errors("\u{05D0}.1com", &options),
+        vec![IdnaError {
+            label: Some(1),
+            kind: IdnaErrorKind::Bidi
+        }]
+    );
+}
+
+#[test]
+fn check_bidi() {
+    let options = Uts46Options {
+        check_bidi: false,
+        ..Uts46Options::default()
+    };
+
+    assert_eq!(
+        domain_to_ascii("\u{05D0}.1com", &options),
+        Ok("xn--4db.1com".to_string())
+    );
+    assert_eq!(
+        domain_to_ascii("1\u{05D0}.com", &options),
+        Ok("xn--1-0hc.com".to_string())
+    );
+    assert_eq!(
+        errors("\u{05D0}.a\u{200D}b", &options),
+        vec![IdnaError {
+            label: Some(1),
+            kind: IdnaErrorKind::Joiners
+        }]
+    );
+}
+
+#[test]
+fn check_joiners() {
+    let options = Uts46Options {
+        check_joiners: false,
+        ..Uts46Options::default()
+    };
+
+    assert_eq!(
+        domain_to_ascii("ok.a\u{200D}b.com", &options),
+        Ok("ok.xn--ab-m1t.com".to_string())
+    );
+    assert_eq!(
+        errors("\u{05D0}.1com.a\u{200D}b", &options),
+        vec![IdnaError {
+            label: Some(1),
+            kind: IdnaErrorKind::Bidi
+        }]
+    );
+}
+
+#[test]
+fn check_hyphens() {
+    let options = Uts46Options {
+        check_hyphens: true,
+        ..Uts46Options::default()
+    };
+
+    assert_eq!(
+        errors("a-.ab--c.ok", &options),
+        vec![
+            IdnaError {
+                label: Some(0),
+                kind: IdnaErrorKind::Hyphens
+            },
+            IdnaError {
+                label: Some(1),
+                kind: IdnaErrorKind::Hyphens
+            },
+        ]
+    );
+    assert_eq!(
+        domain_to_ascii("a-b.com", &options),
+        Ok("a-b.com".to_string())
+    );
+}
+
+#[test]
+fn use_std3_ascii_rules() {
+    let options = Uts46Options {
+        use_std3_ascii_rules: true,
+        ..Uts46Options::default()
+    };
+
+    assert_eq!(
+        errors("ok.a_b.c$d", &options),
+        vec![
+            IdnaError {
+                label: Some(1),
+                kind: IdnaErrorKind::Std3Ascii
+            },
+            IdnaError {
+                label: Some(2),
+                kind: IdnaErrorKind::Std3Ascii
+            },
+        ]
+    );
+}
+
+#[test]
+fn verify_dns_length() {
+    let options = Uts46Options {
+        verify_dns_length: true,
+        ..Uts46Options::default()
+    };
+
+    assert_eq!(
+        errors(&format!("{}a", "a.".repeat(127)), &options),
+        vec![IdnaError {
+            label: None,
+            kind: IdnaErrorKind::DomainTooLong
+        }]
+    );
+    assert_eq!(
+        domain_to_ascii("example.com.", &options),
+        Ok("example.com.".to_string())
+    );
+}
+
+#[test]
+fn failures_always_report_a_label() {
+    for domain in [
+        "\u{05D0}.1com",
+        "\u{05D0}1\u{0661}.\u{05D0}",
+        "\u{0661}.\u{05D0}",
+        "a.\u{0300}b",
+        "xn--a.\u{05D0}",
+        "\u{FFFD}.com",
+    ] {
+        let errors = errors(domain, &Uts46Options::default());
+        assert!(!errors.is_empty(), "{}", domain);
+        assert!(
+            errors.iter().all(|error| error.label.is_some()),
+            "{}",
+            domain
+        );
+    }
+}
+
+#[test]
+fn transitional_processing() {
+    let options = Uts46Options {
+        transitional_processing: true,
+        ..Uts46Options::default()
+    };
+
+    assert_eq!(
+        domain_to_ascii("faß.de", &options),
+        Ok("fass.de".to_string())
+    );
+}
+
+#[test]
+fn parse_host_with_options() {
+    let strict = Uts46Options::strict();
+
+    assert_eq!(
+        parse_host("a_b.example", false, &Uts46Options::default()),
+        Ok(Host::Domain("a_b.example".to_string()))
+    );
+    assert!(matches!(
+        parse_host("a_b.example", false, &strict),
+        Err(HostError::Idna(_))
+    ));
+    assert_eq!(
+        parse_host("%41.example", false, &strict),
+        Ok(Host::Domain("a.example".to_string()))
+    );
+    assert_eq!(
+        parse_host("[::1", false, &strict),
+        Err(HostError::InvalidIpv6)
+    );
+    assert_eq!(
+        parse_host("a.1.2.3.4", false, &strict),
+        Err(HostError::InvalidIpv4)
+    );
+    assert_eq!(
+        parse_host("a b", true, &strict),
+        Err(HostError::ForbiddenHostCodePoint)
+    );
+}
+
+#[test]
+fn domain_to_unicode_with_policy() {
+    assert_eq!(
+        domain_to_unicode("xn--bcher-kva.example", &SafeDisplayPolicy),
+        "bücher.example"
+    );
+    assert_eq!(
+        domain_to_unicode("xn--80ak6aa92e.com", &SafeDisplayPolicy),
+        "xn--80ak6aa92e.com"
+    );
+    assert_eq!(
+        domain_to_unicode("xn--80ak6aa92e.com", &|_: &str, _: &str| true),
+        "аррӏе.com"
+    );
+    assert_eq!(
+        domain_to_unicode("xn--a.xn--bcher-kva.example", &SafeDisplayPolicy),
+        "xn--a.bücher.example"
+    );
+
+    let machine = URLStateMachine::new("https://Bücher.example/", None, None, None);
+    assert_eq!(
+        machine.url.host_unicode().as_deref(),
+        Some("bücher.example")
+    );
+
+    let machine = URLStateMachine::new("https://xn--e1awd7f.com/", None, None, None);
+    assert_eq!(
+        machine.url.host_unicode().as_deref(),
+        Some("xn--e1awd7f.com")
+    );
+}